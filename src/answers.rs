@@ -0,0 +1,190 @@
+//! The recorded answers in `answers.toml`.
+
+use crate::solution::Part;
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Display, fs, io, path::Path};
+
+/// The recorded answers, stored as toml with one table per day:
+///
+/// ```toml
+/// [day1]
+/// part_1 = "751776"
+/// part_2 = "42275090"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    days: BTreeMap<String, DayAnswers>,
+}
+
+/// The answers for one day, or for one example input.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part_1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part_2: Option<String>,
+}
+
+impl DayAnswers {
+    pub fn get(&self, part: Part) -> Option<&str> {
+        self.part(part).as_deref()
+    }
+
+    pub fn check(&self, part: Part, answer: &str) -> Verdict {
+        Verdict::new(self.get(part), answer)
+    }
+
+    fn part(&self, part: Part) -> &Option<String> {
+        match part {
+            Part::One => &self.part_1,
+            Part::Two => &self.part_2,
+        }
+    }
+
+    fn part_mut(&mut self, part: Part) -> &mut Option<String> {
+        match part {
+            Part::One => &mut self.part_1,
+            Part::Two => &mut self.part_2,
+        }
+    }
+}
+
+fn key(day: u8) -> String {
+    format!("day{}", day)
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self> {
+        Ok(toml::from_str(input)?)
+    }
+
+    /// Loads the answers from `path`, a missing file has no answers recorded.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(input) => Self::parse(&input)
+                .wrap_err_with(|| format!("failed to parse answers from {}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => {
+                Err(e).wrap_err_with(|| format!("failed to read answers from {}", path.display()))
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let output = toml::to_string(self)?;
+        fs::write(path, output)
+            .wrap_err_with(|| format!("failed to write answers to {}", path.display()))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.days.get(&key(day))?.get(part)
+    }
+
+    pub fn set(&mut self, day: u8, part: Part, answer: &str) {
+        *self.days.entry(key(day)).or_default().part_mut(part) = Some(answer.to_string());
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &str) -> Verdict {
+        Verdict::new(self.get(day, part), answer)
+    }
+}
+
+/// The result of checking an answer against the recorded one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Match,
+    /// Holds the recorded answer
+    Mismatch(String),
+    Missing,
+}
+
+impl Verdict {
+    fn new(expected: Option<&str>, answer: &str) -> Self {
+        match expected {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer => Verdict::Match,
+            Some(expected) => Verdict::Mismatch(expected.to_string()),
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Match => f.pad("match"),
+            Verdict::Mismatch(_) => f.pad("mismatch"),
+            Verdict::Missing => f.pad("missing"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let answers =
+            Answers::parse("[day1]\npart_1 = \"1\"\npart_2 = \"2\"\n\n[day13]\npart_1 = \"3\"\n")?;
+
+        assert_eq!(answers.get(1, Part::One), Some("1"));
+        assert_eq!(answers.get(1, Part::Two), Some("2"));
+        assert_eq!(answers.get(13, Part::One), Some("3"));
+        assert_eq!(answers.get(13, Part::Two), None);
+        assert_eq!(answers.get(2, Part::One), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check() {
+        let mut answers = Answers::default();
+        answers.set(5, Part::Two, "696");
+
+        assert_eq!(answers.check(5, Part::Two, "696"), Verdict::Match);
+        assert_eq!(
+            answers.check(5, Part::Two, "697"),
+            Verdict::Mismatch("696".into())
+        );
+        assert_eq!(answers.check(5, Part::One, "938"), Verdict::Missing);
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<()> {
+        let mut answers = Answers::default();
+        answers.set(2, Part::One, "600");
+        answers.set(10, Part::Two, "21156911906816");
+
+        let output = toml::to_string(&answers)?;
+        assert_eq!(Answers::parse(&output)?, answers);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recorded_answers() -> Result<()> {
+        let answers = Answers::load(Path::new("answers.toml"))?;
+
+        // the quick days, the rest are covered by `aoc2020 verify`
+        for day in 1..=9 {
+            let solution = crate::get_day(day).unwrap();
+            let input = crate::input::InputSource::Default.load(Path::new("data"), day)?;
+            let parsed = solution.parse_input(&input)?;
+
+            for part in Part::all().iter().cloned() {
+                let answer = solution.solve(&parsed, part)?;
+                assert_eq!(
+                    answers.check(day, part, &answer),
+                    Verdict::Match,
+                    "day {} part {}",
+                    day,
+                    part
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! Timing the parsing and both parts of a day.
+
+use crate::solution::{DynSolution, Part};
+use eyre::{ensure, Result};
+use serde::Serialize;
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+/// What is being timed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Stage {
+    #[serde(rename = "parse")]
+    Parse,
+    #[serde(rename = "part_1")]
+    Part1,
+    #[serde(rename = "part_2")]
+    Part2,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Parse => f.pad("parse"),
+            Stage::Part1 => f.pad("part 1"),
+            Stage::Part2 => f.pad("part 2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// The median of an even number of samples is the upper of the two middle ones.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort_unstable();
+
+        Some(Stats {
+            min: *samples.first()?,
+            median: samples[samples.len() / 2],
+            max: *samples.last()?,
+        })
+    }
+}
+
+/// One row of the benchmark results, all times are in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchResult {
+    pub day: u8,
+    pub stage: Stage,
+    pub iterations: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub max_ms: f64,
+}
+
+impl BenchResult {
+    fn new(day: u8, stage: Stage, iterations: usize, stats: Stats) -> Self {
+        Self {
+            day,
+            stage,
+            iterations,
+            min_ms: as_ms(stats.min),
+            median_ms: as_ms(stats.median),
+            max_ms: as_ms(stats.max),
+        }
+    }
+}
+
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn measure<F>(iterations: usize, mut f: F) -> Result<Stats>
+where
+    F: FnMut() -> Result<()>,
+{
+    let mut samples = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let now = Instant::now();
+        f()?;
+        samples.push(now.elapsed());
+    }
+
+    Ok(Stats::from_samples(samples).unwrap())
+}
+
+/// Times parsing and both parts of a day, `iterations` times each.
+pub fn bench_day(
+    day: u8,
+    solution: &dyn DynSolution,
+    input: &str,
+    iterations: usize,
+) -> Result<Vec<BenchResult>> {
+    ensure!(iterations > 0, "need at least one iteration");
+
+    let parse = measure(iterations, || solution.parse_input(input).map(drop))?;
+
+    let parsed = solution.parse_input(input)?;
+    let part_1 = measure(iterations, || solution.solve(&parsed, Part::One).map(drop))?;
+    let part_2 = measure(iterations, || solution.solve(&parsed, Part::Two).map(drop))?;
+
+    Ok(vec![
+        BenchResult::new(day, Stage::Parse, iterations, parse),
+        BenchResult::new(day, Stage::Part1, iterations, part_1),
+        BenchResult::new(day, Stage::Part2, iterations, part_2),
+    ])
+}
+
+pub fn print_table(results: &[BenchResult]) {
+    println!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}",
+        "day", "stage", "min ms", "median ms", "max ms"
+    );
+
+    for result in results {
+        println!(
+            "{:>3}  {:<6}  {:>10.3}  {:>10.3}  {:>10.3}",
+            result.day, result.stage, result.min_ms, result.median_ms, result.max_ms
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_stats() {
+        let tests = vec![
+            (vec![ms(3)], (ms(3), ms(3), ms(3))),
+            (vec![ms(3), ms(1), ms(2)], (ms(1), ms(2), ms(3))),
+            (vec![ms(4), ms(1), ms(3), ms(2)], (ms(1), ms(3), ms(4))),
+        ];
+
+        for (samples, (min, median, max)) in tests {
+            assert_eq!(
+                Stats::from_samples(samples),
+                Some(Stats { min, median, max })
+            );
+        }
+
+        assert_eq!(Stats::from_samples(vec![]), None);
+    }
+
+    #[test]
+    fn test_bench_day() -> Result<()> {
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        let results = bench_day(1, &crate::day1::Day1, input, 3)?;
+
+        let stages: Vec<_> = results.iter().map(|r| r.stage).collect();
+        assert_eq!(stages, vec![Stage::Parse, Stage::Part1, Stage::Part2]);
+
+        for result in results {
+            assert_eq!(result.day, 1);
+            assert_eq!(result.iterations, 3);
+            assert!(result.min_ms <= result.median_ms && result.median_ms <= result.max_ms);
+        }
+
+        assert!(bench_day(1, &crate::day1::Day1, input, 0).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//! Answers from earlier runs, so unchanged inputs do not have to be solved again.
+
+use crate::solution::Part;
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, env, fs, io, path::Path, time::UNIX_EPOCH};
+
+/// The cached answers, stored as toml:
+///
+/// ```toml
+/// version = "0.1.0-5f0d6bc4a3e1f2d9"
+///
+/// [answers]
+/// day15_part2_8c3a1b6f0e2d4a17 = "18831"
+/// ```
+///
+/// The answers are keyed by the day, the part and the hash of the input, and only used by
+/// the binary with the same version.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cache {
+    version: String,
+    answers: BTreeMap<String, String>,
+}
+
+/// The 64 bit FNV-1a hash, unlike the std hashers it is the same on every platform and
+/// Rust version.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Identifies the running binary by the package version and the size and modification
+/// time of the executable, so answers from before a rebuild are not used.
+pub fn binary_version() -> Result<String> {
+    let exe = env::current_exe()?;
+    let metadata = fs::metadata(&exe)
+        .wrap_err_with(|| format!("failed to read the metadata of {}", exe.display()))?;
+    let modified = metadata.modified()?.duration_since(UNIX_EPOCH)?.as_nanos();
+    let build = format!("{}-{}", metadata.len(), modified);
+
+    Ok(format!(
+        "{}-{:016x}",
+        env!("CARGO_PKG_VERSION"),
+        fnv1a(build.as_bytes())
+    ))
+}
+
+fn key(day: u8, part: Part, input: &str) -> String {
+    format!("day{}_part{}_{:016x}", day, part, fnv1a(input.as_bytes()))
+}
+
+impl Cache {
+    /// An empty cache for the binary with `version`.
+    pub fn new(version: &str) -> Self {
+        Self {
+            version: version.to_string(),
+            answers: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the cache from `path`, a missing file, or one written by a binary with another
+    /// version, has no answers.
+    pub fn load(path: &Path, version: &str) -> Result<Self> {
+        let cache: Self = match fs::read_to_string(path) {
+            Ok(input) => toml::from_str(&input)
+                .wrap_err_with(|| format!("failed to parse the cache {}", path.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::new(version),
+            Err(e) => {
+                return Err(e)
+                    .wrap_err_with(|| format!("failed to read the cache {}", path.display()))
+            }
+        };
+
+        if cache.version == version {
+            Ok(cache)
+        } else {
+            Ok(Self::new(version))
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let output = toml::to_string(self)?;
+        fs::write(path, output)
+            .wrap_err_with(|| format!("failed to write the cache {}", path.display()))
+    }
+
+    /// The answer to `part` of `day` for this exact `input`, if it is cached.
+    pub fn get(&self, day: u8, part: Part, input: &str) -> Option<&str> {
+        self.answers.get(&key(day, part, input)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: Part, input: &str, answer: &str) {
+        self.answers
+            .insert(key(day, part, input), answer.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_get() {
+        let mut cache = Cache::new("1");
+        cache.insert(15, Part::Two, "0,3,6\n", "175594");
+
+        assert_eq!(cache.get(15, Part::Two, "0,3,6\n"), Some("175594"));
+        assert_eq!(cache.get(15, Part::One, "0,3,6\n"), None);
+        assert_eq!(cache.get(14, Part::Two, "0,3,6\n"), None);
+        assert_eq!(cache.get(15, Part::Two, "0,3,7\n"), None);
+    }
+
+    #[test]
+    fn test_load() -> Result<()> {
+        let path = env::temp_dir().join(format!("aoc2020_cache_{}.toml", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(Cache::load(&path, "1")?, Cache::new("1"));
+
+        let mut cache = Cache::new("1");
+        cache.insert(15, Part::Two, "0,3,6\n", "175594");
+        cache.save(&path)?;
+
+        assert_eq!(Cache::load(&path, "1")?, cache);
+        assert_eq!(
+            Cache::load(&path, "2")?,
+            Cache::new("2"),
+            "another binary does not use the answers"
+        );
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_cached() {
+        let cache = Mutex::new(Cache::new("1"));
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        let answers = |refresh| {
+            crate::solve_cached(
+                1,
+                Ok(input.into()),
+                &[Part::One, Part::Two],
+                &cache,
+                refresh,
+            )
+            .into_iter()
+            .map(|record| (record.answer.unwrap(), record.cached))
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            answers(false),
+            vec![("514579".into(), false), ("241861950".into(), false)]
+        );
+        assert_eq!(
+            answers(false),
+            vec![("514579".into(), true), ("241861950".into(), true)]
+        );
+        assert_eq!(
+            answers(true),
+            vec![("514579".into(), false), ("241861950".into(), false)],
+            "refreshing solves the parts again"
+        );
+        assert_eq!(
+            cache.lock().unwrap().get(1, Part::Two, input),
+            Some("241861950")
+        );
+    }
+}
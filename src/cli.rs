@@ -0,0 +1,410 @@
+use aoc2020::{
+    day2::{self, PasswordPolicy},
+    day3::Slope,
+    logging::LogFormat,
+    output::Format,
+    Part,
+};
+use std::{
+    collections::BTreeSet,
+    num::ParseIntError,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use structopt::StructOpt;
+use thiserror::Error;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "aoc2020", about = "Advent Of Code 2020")]
+pub struct Opt {
+    /// Log filter used when `RUST_LOG` is not set, e.g. `debug` or `aoc2020::day19=trace`
+    #[structopt(long, default_value = "info", global = true)]
+    pub log_level: String,
+
+    /// Log format: full, pretty, compact or json
+    #[structopt(long, default_value = "full", global = true)]
+    pub log_format: LogFormat,
+
+    /// Append the logs to this file instead of writing them to stderr
+    #[structopt(long, parse(from_os_str), global = true)]
+    pub log_file: Option<PathBuf>,
+
+    /// Log the time spent in each span when it closes
+    #[structopt(long, global = true)]
+    pub log_spans: bool,
+
+    #[structopt(subcommand)]
+    pub cmd: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Run the solutions for the selected days
+    Run {
+        /// Days to run, e.g. `13`, `10..=14`, `1,3,5` or `all`
+        #[structopt(default_value = "all")]
+        days: DaySelection,
+
+        /// Only run the given part (1 or 2)
+        #[structopt(short, long)]
+        part: Option<Part>,
+
+        /// Read the input from this file instead, use `-` for stdin
+        #[structopt(short, long, parse(from_os_str))]
+        input: Option<PathBuf>,
+
+        /// Directory containing the `dayN.txt` inputs
+        #[structopt(long, default_value = "data", parse(from_os_str))]
+        data_dir: PathBuf,
+
+        /// Run the inputs in `<data-dir>/<profile>/` and print the answers of each profile
+        /// side by side, can be given more than once, `all` runs every profile
+        #[structopt(long, conflicts_with = "input")]
+        profile: Vec<String>,
+
+        /// Output format: plain, json or csv
+        #[structopt(short, long, default_value = "plain")]
+        format: Format,
+
+        /// Number of days to run at the same time, 0 uses one thread per CPU
+        #[structopt(short, long, default_value = "1")]
+        jobs: usize,
+
+        /// Keep the answers in this file and print them from it, instead of solving the
+        /// parts again, while the input and the binary are unchanged
+        #[structopt(long, parse(from_os_str))]
+        cache: Option<PathBuf>,
+
+        /// Solve every part even when its answer is cached, and update the cache
+        #[structopt(long, requires = "cache")]
+        no_cache: bool,
+    },
+    /// Check the answers against the recorded ones
+    Verify {
+        /// Days to verify, e.g. `13`, `10..=14`, `1,3,5` or `all`
+        #[structopt(default_value = "all")]
+        days: DaySelection,
+
+        /// Directory containing the `dayN.txt` inputs
+        #[structopt(long, default_value = "data", parse(from_os_str))]
+        data_dir: PathBuf,
+
+        /// File with the recorded answers
+        #[structopt(long, default_value = "answers.toml", parse(from_os_str))]
+        answers: PathBuf,
+
+        /// Record the answers that are missing from the answers file
+        #[structopt(long)]
+        record: bool,
+
+        /// Check the example inputs, `dayN_<name>.txt`, against their
+        /// `dayN_<name>.answers.toml` files instead
+        #[structopt(long, conflicts_with = "record")]
+        examples: bool,
+    },
+    /// Time parsing and both parts of the selected days
+    Bench {
+        /// Days to benchmark, e.g. `13`, `10..=14`, `1,3,5` or `all`
+        #[structopt(default_value = "all")]
+        days: DaySelection,
+
+        /// How many times each stage is run
+        #[structopt(short = "n", long, default_value = "10")]
+        iterations: usize,
+
+        /// Directory containing the `dayN.txt` inputs
+        #[structopt(long, default_value = "data", parse(from_os_str))]
+        data_dir: PathBuf,
+
+        /// Print the results as JSON
+        #[structopt(long)]
+        json: bool,
+    },
+    /// Re-run a day whenever its input changes
+    Watch {
+        /// The day to run
+        day: u8,
+
+        /// Watch this file instead of `dayN.txt` in the data directory, can be given more
+        /// than once
+        #[structopt(short, long, parse(from_os_str))]
+        input: Vec<PathBuf>,
+
+        /// Directory containing the `dayN.txt` inputs
+        #[structopt(long, default_value = "data", parse(from_os_str))]
+        data_dir: PathBuf,
+
+        /// How often the files are checked, in milliseconds
+        #[structopt(long, default_value = "500")]
+        interval: u64,
+    },
+    /// Animate how day 11, 17 or 20 evolves, step by step
+    Visualize {
+        /// The day to animate: 11, 17 or 20
+        day: u8,
+
+        /// The seating rules of day 11, or the number of dimensions of day 17 (1 for three,
+        /// 2 for four)
+        #[structopt(short, long, default_value = "1")]
+        part: Part,
+
+        /// Read the input from this file instead, use `-` for stdin
+        #[structopt(short, long, parse(from_os_str))]
+        input: Option<PathBuf>,
+
+        /// Directory containing the `dayN.txt` inputs
+        #[structopt(long, default_value = "data", parse(from_os_str))]
+        data_dir: PathBuf,
+
+        /// How long each frame is shown while playing, in milliseconds
+        #[structopt(long, default_value = "200")]
+        delay: u64,
+    },
+    /// Find the expenses of day 1 that sum to a target, and their product
+    Expenses {
+        /// How many expenses to combine
+        #[structopt(short, default_value = "2")]
+        k: usize,
+
+        /// The sum to look for
+        #[structopt(short, long, default_value = "2020")]
+        target: u64,
+
+        /// Print every combination that sums to the target, not only the first one
+        #[structopt(long)]
+        all: bool,
+
+        /// Read the input from this file instead, use `-` for stdin
+        #[structopt(short, long, parse(from_os_str))]
+        input: Option<PathBuf>,
+
+        /// Directory containing the `dayN.txt` inputs
+        #[structopt(long, default_value = "data", parse(from_os_str))]
+        data_dir: PathBuf,
+    },
+    /// Count the day 2 passwords that follow each policy
+    Passwords {
+        /// The policy to check: count, position, pattern=<regex>, forbid=<chars> or
+        /// distinct=<n>, can be given more than once, defaults to count and position
+        #[structopt(short, long, parse(try_from_str = day2::parse_policy))]
+        policy: Vec<Box<dyn PasswordPolicy>>,
+
+        /// Print every password with the verdict of each policy, and why it is invalid
+        #[structopt(long)]
+        report: bool,
+
+        /// Print the passwords that one of two policies accepts and the other rejects
+        #[structopt(long, conflicts_with = "report")]
+        diff: bool,
+
+        /// Read the input from this file instead, use `-` for stdin
+        #[structopt(short, long, parse(from_os_str))]
+        input: Option<PathBuf>,
+
+        /// Directory containing the `dayN.txt` inputs
+        #[structopt(long, default_value = "data", parse(from_os_str))]
+        data_dir: PathBuf,
+    },
+    /// Count the day 3 trees on each slope, the puzzle's slopes by default
+    Slopes {
+        /// A slope to go down, `<right>,<down>`, can be given more than once
+        #[structopt(short, long, conflicts_with = "sweep")]
+        slope: Vec<Slope>,
+
+        /// Try every slope up to --max-right right and --max-down down
+        #[structopt(long)]
+        sweep: bool,
+
+        /// The furthest right a swept slope goes at a time
+        #[structopt(long, default_value = "10")]
+        max_right: usize,
+
+        /// The furthest down a swept slope goes at a time
+        #[structopt(long, default_value = "3")]
+        max_down: usize,
+
+        /// Read the input from this file instead, use `-` for stdin
+        #[structopt(short, long, parse(from_os_str))]
+        input: Option<PathBuf>,
+
+        /// Directory containing the `dayN.txt` inputs
+        #[structopt(long, default_value = "data", parse(from_os_str))]
+        data_dir: PathBuf,
+    },
+    /// Print a random input for a day, e.g. to pipe into `run <day> --input -`
+    Gen {
+        /// The day to generate the input for
+        day: u8,
+
+        /// How large the input is, what it counts depends on the day, defaults to about the
+        /// size of the puzzle input
+        #[structopt(short, long)]
+        size: Option<usize>,
+
+        /// The same seed always gives the same input
+        #[structopt(long, default_value = "0")]
+        seed: u64,
+    },
+    /// Create and register the module and data files for a new day
+    New {
+        /// The day to create, e.g. `21`
+        day: u8,
+
+        /// Root of the repository, containing `src` and `data`
+        #[structopt(long, default_value = ".", parse(from_os_str))]
+        root: PathBuf,
+    },
+}
+
+/// Whether `run` uses the answers of earlier runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheMode {
+    Off,
+    /// Use the answers cached in the file and add the new ones
+    Use(PathBuf),
+    /// Solve every part and replace the cached answers
+    Refresh(PathBuf),
+}
+
+impl CacheMode {
+    pub fn new(path: Option<PathBuf>, no_cache: bool) -> Self {
+        match path {
+            None => CacheMode::Off,
+            Some(path) if no_cache => CacheMode::Refresh(path),
+            Some(path) => CacheMode::Use(path),
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            CacheMode::Off => None,
+            CacheMode::Use(path) | CacheMode::Refresh(path) => Some(path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DaySelection {
+    All,
+    Days(BTreeSet<u8>),
+}
+
+impl DaySelection {
+    /// Resolves the selection against the available days, in ascending order.
+    pub fn resolve(&self, available: &[u8]) -> Result<Vec<u8>, DaySelectionError> {
+        match self {
+            DaySelection::All => {
+                let mut days = available.to_vec();
+                days.sort_unstable();
+                Ok(days)
+            }
+            DaySelection::Days(days) => days
+                .iter()
+                .map(|day| {
+                    if available.contains(day) {
+                        Ok(*day)
+                    } else {
+                        Err(DaySelectionError::NoSuchDay(*day))
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DaySelectionError {
+    #[error("invalid day: '{0}'")]
+    InvalidDay(String, #[source] ParseIntError),
+    #[error("invalid range: '{0}'")]
+    InvalidRange(String),
+    #[error("day {0} does not exist")]
+    NoSuchDay(u8),
+}
+
+fn parse_day(s: &str) -> Result<u8, DaySelectionError> {
+    u8::from_str(s.trim()).map_err(|e| DaySelectionError::InvalidDay(s.to_string(), e))
+}
+
+impl FromStr for DaySelection {
+    type Err = DaySelectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "all" {
+            return Ok(DaySelection::All);
+        }
+
+        let mut days = BTreeSet::new();
+
+        for item in s.split(',') {
+            if let Some((start, end)) = item.split_once("..=") {
+                let (start, end) = (parse_day(start)?, parse_day(end)?);
+                if start > end {
+                    return Err(DaySelectionError::InvalidRange(item.to_string()));
+                }
+                days.extend(start..=end);
+            } else if let Some((start, end)) = item.split_once("..") {
+                let (start, end) = (parse_day(start)?, parse_day(end)?);
+                if start >= end {
+                    return Err(DaySelectionError::InvalidRange(item.to_string()));
+                }
+                days.extend(start..end);
+            } else {
+                days.insert(parse_day(item)?);
+            }
+        }
+
+        Ok(DaySelection::Days(days))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn days(days: &[u8]) -> DaySelection {
+        DaySelection::Days(days.iter().cloned().collect())
+    }
+
+    #[test]
+    fn test_parse_day_selection() {
+        let tests = vec![
+            ("all", DaySelection::All),
+            ("13", days(&[13])),
+            ("10..=14", days(&[10, 11, 12, 13, 14])),
+            ("10..14", days(&[10, 11, 12, 13])),
+            ("1,3,5", days(&[1, 3, 5])),
+            ("5,1..=3,2", days(&[1, 2, 3, 5])),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(DaySelection::from_str(input), Ok(want), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_day_selection_invalid() {
+        assert!(DaySelection::from_str("x").is_err());
+        assert!(DaySelection::from_str("1..").is_err());
+        assert_eq!(
+            DaySelection::from_str("5..=3"),
+            Err(DaySelectionError::InvalidRange("5..=3".into()))
+        );
+        assert_eq!(
+            DaySelection::from_str("3..3"),
+            Err(DaySelectionError::InvalidRange("3..3".into()))
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let available = [3, 1, 2];
+
+        assert_eq!(DaySelection::All.resolve(&available), Ok(vec![1, 2, 3]));
+        assert_eq!(days(&[2, 3]).resolve(&available), Ok(vec![2, 3]));
+        assert_eq!(
+            days(&[2, 21]).resolve(&available),
+            Err(DaySelectionError::NoSuchDay(21))
+        );
+    }
+}
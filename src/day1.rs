@@ -1,36 +1,408 @@
-use std::str::FromStr;
+//! Day 1: Report Repair, find the expenses that sum to 2020.
 
-pub fn run() {
-    let input = include_str!("../data/day1.txt")
-        .split_ascii_whitespace()
-        .map(|a| u64::from_str(a).unwrap())
-        .collect::<Vec<_>>();
+use crate::solution::Solution;
+use eyre::{eyre, Result, WrapErr};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    num::ParseIntError,
+    str::FromStr,
+};
+use thiserror::Error;
+use tracing::{debug, warn};
 
-    println!("day 1: {}, {}", calc(&input), calc2(&input));
+/// The sum the expenses of the puzzle add up to.
+pub const TARGET: u64 = 2020;
+
+/// Multiplies the two, and then the three, expenses that sum to 2020.
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = ExpenseReport;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        answer(input, 2)
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        answer(input, 3)
+    }
 }
 
-fn calc(input: &[u64]) -> u64 {
-    for i in input {
-        for j in input {
-            if i + j == 2020 {
-                return i * j;
-            }
+/// The product of the `k` entries that sum to [`TARGET`], with a warning if more than
+/// one combination does.
+fn answer(report: &ExpenseReport, k: usize) -> Result<u64> {
+    let audit = report.audit(k, TARGET)?;
+    debug!(%audit);
+    if !audit.is_unique() {
+        warn!(
+            "{} combinations of {} entries sum to {}, the answer is the product of the first: {}",
+            audit.combinations, k, TARGET, audit
+        );
+    }
+
+    audit
+        .product()
+        .ok_or_else(|| eyre!("the product of the entries overflows"))
+}
+
+/// The expenses, and the line each one is on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpenseReport {
+    pub entries: Vec<u64>,
+    /// The line number of each entry, counted from 1
+    pub lines: Vec<usize>,
+}
+
+impl ExpenseReport {
+    /// The line numbers and values of the entries at `indices`.
+    pub fn combination(&self, indices: &[usize]) -> Vec<(usize, u64)> {
+        indices
+            .iter()
+            .map(|&i| (self.lines[i], self.entries[i]))
+            .collect()
+    }
+
+    /// Which `k` entries sum to `target` and how many combinations do, an error if none
+    /// does.
+    pub fn audit(&self, k: usize, target: u64) -> Result<Audit> {
+        let combinations = find_k_sum(&self.entries, k, target);
+        let first = combinations
+            .first()
+            .ok_or_else(|| eyre!("no {} entries sum to {}", k, target))?;
+
+        Ok(Audit {
+            entries: self.combination(first),
+            target,
+            combinations: combinations.len(),
+        })
+    }
+}
+
+/// The entries that sum to the target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audit {
+    /// The line number and value of each entry of the first combination
+    pub entries: Vec<(usize, u64)>,
+    pub target: u64,
+    /// How many combinations of entries sum to the target
+    pub combinations: usize,
+}
+
+impl Audit {
+    /// Whether the entries are the only ones that sum to the target.
+    pub fn is_unique(&self) -> bool {
+        self.combinations == 1
+    }
+
+    /// The product of the entries, `None` if it does not fit in a `u64`.
+    pub fn product(&self) -> Option<u64> {
+        product(&self.entries)
+    }
+}
+
+/// `1721 (line 1) + 299 (line 4) = 2020, product 514579`, followed by how many
+/// combinations there are if there is more than one.
+impl Display for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format_sum(&self.entries, self.target))?;
+        if !self.is_unique() {
+            write!(f, " (1 of {} combinations)", self.combinations)?;
+        }
+        Ok(())
+    }
+}
+
+/// The entries, with their line numbers, and their sum and product.
+pub fn format_sum(entries: &[(usize, u64)], target: u64) -> String {
+    let terms: Vec<String> = entries
+        .iter()
+        .map(|(line, entry)| format!("{} (line {})", entry, line))
+        .collect();
+    match product(entries) {
+        Some(product) => format!("{} = {}, product {}", terms.join(" + "), target, product),
+        None => format!("{} = {}, product too large", terms.join(" + "), target),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ExpenseParseError {
+    #[error("invalid expense: '{0}'")]
+    InvalidExpense(String, #[source] ParseIntError),
+}
+
+/// Parses one expense per line, blank lines are skipped.
+pub fn parse_input(input: &str) -> Result<ExpenseReport> {
+    let mut report = ExpenseReport {
+        entries: Vec::new(),
+        lines: Vec::new(),
+    };
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let entry = u64::from_str(line.trim())
+            .map_err(|e| ExpenseParseError::InvalidExpense(line.to_string(), e))
+            .wrap_err_with(|| format!("invalid input on line {}", i + 1))?;
+        report.entries.push(entry);
+        report.lines.push(i + 1);
+    }
+
+    Ok(report)
+}
+
+/// Every combination of `k` entries, at distinct indices, that sums to `target`. Each
+/// combination is the indices of its entries in ascending order, and is found once.
+///
+/// The pairs are found with a lookup of the entries by value, so `k = 2` takes O(n) and
+/// every further entry multiplies that by n.
+pub fn find_k_sum(entries: &[u64], k: usize, target: u64) -> Vec<Vec<usize>> {
+    let mut combinations = Vec::new();
+    KSum::new(entries).search(k, target, 0, &mut Vec::new(), &mut |indices| {
+        combinations.push(indices.to_vec());
+        true
+    });
+
+    combinations
+}
+
+/// The first combination [`find_k_sum`] finds, without looking for the others.
+pub fn find_first_k_sum(entries: &[u64], k: usize, target: u64) -> Option<Vec<usize>> {
+    let mut first = None;
+    KSum::new(entries).search(k, target, 0, &mut Vec::new(), &mut |indices| {
+        first = Some(indices.to_vec());
+        false
+    });
+
+    first
+}
+
+fn product(entries: &[(usize, u64)]) -> Option<u64> {
+    entries
+        .iter()
+        .try_fold(1u64, |product, &(_, entry)| product.checked_mul(entry))
+}
+
+struct KSum<'a> {
+    entries: &'a [u64],
+    /// The indices of each value, ascending
+    indices: HashMap<u64, Vec<usize>>,
+}
+
+impl<'a> KSum<'a> {
+    fn new(entries: &'a [u64]) -> Self {
+        let mut indices: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, &entry) in entries.iter().enumerate() {
+            indices.entry(entry).or_default().push(i);
         }
+
+        Self { entries, indices }
+    }
+
+    /// The indices at or after `start` with the value `value`.
+    fn indices_of(&self, value: u64, start: usize) -> impl Iterator<Item = usize> + '_ {
+        self.indices
+            .get(&value)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .filter(move |&i| i >= start)
     }
 
-    0
+    /// Extends `chosen` with `k` more indices from `start` on whose entries sum to
+    /// `target`, calling `found` with every combination until it returns false. Returns
+    /// false when the search was stopped.
+    fn search(
+        &self,
+        k: usize,
+        target: u64,
+        start: usize,
+        chosen: &mut Vec<usize>,
+        found: &mut dyn FnMut(&[usize]) -> bool,
+    ) -> bool {
+        match k {
+            0 if target == 0 => found(chosen),
+            0 => true,
+            1 => {
+                for i in self.indices_of(target, start) {
+                    chosen.push(i);
+                    let more = found(chosen);
+                    chosen.pop();
+                    if !more {
+                        return false;
+                    }
+                }
+                true
+            }
+            2 => {
+                for (j, &entry) in self.entries.iter().enumerate().skip(start) {
+                    if entry > target {
+                        continue;
+                    }
+                    for i in self.indices_of(target - entry, start) {
+                        if i >= j {
+                            break;
+                        }
+                        chosen.extend_from_slice(&[i, j]);
+                        let more = found(chosen);
+                        chosen.truncate(chosen.len() - 2);
+                        if !more {
+                            return false;
+                        }
+                    }
+                }
+                true
+            }
+            _ => {
+                for (i, &entry) in self.entries.iter().enumerate().skip(start) {
+                    if entry > target {
+                        continue;
+                    }
+                    chosen.push(i);
+                    let more = self.search(k - 1, target - entry, i + 1, chosen, found);
+                    chosen.pop();
+                    if !more {
+                        return false;
+                    }
+                }
+                true
+            }
+        }
+    }
 }
 
-fn calc2(input: &[u64]) -> u64 {
-    for i in input {
-        for j in input {
-            for k in input {
-                if i + j + k == 2020 {
-                    return i * j * k;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() -> Result<()> {
+        let input = parse_input("1721\n979\n366\n299\n675\n1456\n")?;
+
+        assert_eq!(Day1.part_1(&input)?, 514579);
+        assert_eq!(Day1.part_2(&input)?, 241861950);
+        assert_eq!(
+            Day1.part_1(&parse_input("1\n2\n")?)
+                .unwrap_err()
+                .to_string(),
+            "no 2 entries sum to 2020"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit() -> Result<()> {
+        let report = parse_input("1721\n979\n\n366\n299\n675\n1456\n")?;
+        assert_eq!(
+            report.lines,
+            vec![1, 2, 4, 5, 6, 7],
+            "blank lines are skipped"
+        );
+
+        let audit = report.audit(2, 2020)?;
+        assert_eq!(audit.entries, vec![(1, 1721), (5, 299)]);
+        assert!(audit.is_unique());
+        assert_eq!(audit.product(), Some(514579));
+        assert_eq!(
+            audit.to_string(),
+            "1721 (line 1) + 299 (line 5) = 2020, product 514579"
+        );
+
+        let audit = report.audit(2, 1345)?;
+        assert_eq!(audit.product(), Some(979 * 366));
+
+        let audit = parse_input("1000\n1020\n10\n1010\n1000\n")?.audit(2, 2020)?;
+        assert!(!audit.is_unique());
+        assert_eq!(
+            audit.to_string(),
+            "1000 (line 1) + 1020 (line 2) = 2020, product 1020000 (1 of 2 combinations)"
+        );
+
+        assert_eq!(
+            report.audit(4, 2020).unwrap_err().to_string(),
+            "no 4 entries sum to 2020"
+        );
+
+        let report = ExpenseReport {
+            entries: vec![u64::MAX / 2, 2, 3],
+            lines: vec![1, 2, 3],
+        };
+        assert_eq!(report.audit(2, u64::MAX / 2 + 3)?.product(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_k_sum() {
+        let entries = [1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(find_k_sum(&entries, 2, 2020), vec![vec![0, 3]]);
+        assert_eq!(find_k_sum(&entries, 3, 2020), vec![vec![1, 2, 4]]);
+        assert_eq!(find_first_k_sum(&entries, 3, 2020), Some(vec![1, 2, 4]));
+        assert_eq!(find_k_sum(&entries, 1, 366), vec![vec![2]]);
+        assert_eq!(find_k_sum(&entries, 0, 0), vec![Vec::<usize>::new()]);
+        assert!(find_k_sum(&entries, 4, 2020).is_empty());
+        assert_eq!(
+            find_first_k_sum(&entries, 7, 5496),
+            None,
+            "more than there are"
+        );
+
+        assert_eq!(
+            find_k_sum(&[1010, 5, 1010, 1010], 2, 2020),
+            vec![vec![0, 2], vec![0, 3], vec![2, 3]],
+            "an entry is not paired with itself"
+        );
+        assert!(find_k_sum(&[1010, 5], 2, 2020).is_empty());
+        assert_eq!(
+            find_k_sum(&[1, 2, 3, 4, 5], 3, 9),
+            vec![vec![0, 2, 4], vec![1, 2, 3]]
+        );
+    }
+
+    #[test]
+    fn test_find_k_sum_brute_force() {
+        let entries: Vec<u64> = (0..16).map(|i| (i * 37 + 11) % 50).collect();
+
+        for k in 1..=4 {
+            for target in (0..150).step_by(7) {
+                let mut want = Vec::new();
+                for combination in 0u32..1 << entries.len() {
+                    if combination.count_ones() as usize != k {
+                        continue;
+                    }
+                    let indices: Vec<usize> = (0..entries.len())
+                        .filter(|i| combination & 1 << i != 0)
+                        .collect();
+                    if indices.iter().map(|&i| entries[i]).sum::<u64>() == target {
+                        want.push(indices);
+                    }
                 }
+
+                let mut got = find_k_sum(&entries, k, target);
+                got.sort();
+                want.sort();
+                assert_eq!(got, want, "k: {}, target: {}", k, target);
             }
         }
     }
 
-    0
+    #[test]
+    fn test_parse_invalid() {
+        let err = parse_input("1721\n97x\n366\n").unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", err),
+            "invalid input on line 2: invalid expense: '97x': invalid digit found in string"
+        );
+    }
 }
@@ -1,26 +1,38 @@
-use eyre::{bail, Result};
-use std::{collections::HashMap, str::FromStr, time::Instant};
-use tracing::{debug, info, instrument};
+//! Day 10: Adapter Array, chain the joltage adapters.
 
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day10.txt");
-    let mut parsed = parse_input(input)?;
-    let diffs = find_diffs(&mut parsed)?;
-    let answer = diffs.0 * diffs.2;
+use crate::solution::Solution;
+use eyre::{bail, eyre, Result};
+use std::{collections::HashMap, str::FromStr};
+use tracing::{debug, instrument};
 
-    info!(?diffs, answer);
+/// Multiplies the 1 and 3 jolt differences of the full chain, and then counts the ways
+/// the adapters can be arranged.
+pub struct Day10;
 
-    let now = Instant::now();
-    let num_arrangements = find_num_arrangements(parsed);
-    let elapsed_ms = now.elapsed().as_millis();
+impl Solution for Day10 {
+    type Input = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    info!(num_arrangements, ?elapsed_ms);
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        let diffs = find_diffs(&mut input.clone())?;
+        debug!(?diffs);
+
+        Ok(diffs.0 * diffs.2)
+    }
 
-    Ok(())
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        find_num_arrangements(input.clone())
+    }
 }
 
+/// Parses one adapter joltage per line.
 #[instrument]
-fn parse_input(input: &str) -> Result<Vec<usize>> {
+pub fn parse_input(input: &str) -> Result<Vec<usize>> {
     Ok(input
         .trim()
         .lines()
@@ -28,7 +40,9 @@ fn parse_input(input: &str) -> Result<Vec<usize>> {
         .collect::<Result<Vec<usize>, _>>()?)
 }
 
-fn find_diffs(input: &mut [usize]) -> Result<(usize, usize, usize)> {
+/// Sorts the adapters and counts the 1, 2 and 3 jolt differences from the outlet to the
+/// device.
+pub fn find_diffs(input: &mut [usize]) -> Result<(usize, usize, usize)> {
     input.sort_unstable();
 
     let mut last = 0; // outlet is 0
@@ -60,15 +74,15 @@ fn find_diffs(input: &mut [usize]) -> Result<(usize, usize, usize)> {
 }
 
 #[allow(dead_code)]
-fn find_num_arrangements_sloooooow(mut input: Vec<usize>) -> usize {
+fn find_num_arrangements_sloooooow(mut input: Vec<usize>) -> Result<usize> {
     input.sort_unstable();
 
-    let computer = *input.last().unwrap() + 3;
+    let computer = input.last().ok_or_else(|| eyre!("no adapters"))? + 3;
     input.push(computer);
 
     debug!(?input, computer);
 
-    r(0, -1, &input)
+    Ok(r(0, -1, &input))
 }
 
 #[instrument(skip(input))] // this makes it a lot slower, 8 ms vs 800 ms for day10_test_2
@@ -107,10 +121,11 @@ fn get_num_arrangements(
             .unwrap_or_default())
 }
 
-fn find_num_arrangements(mut input: Vec<usize>) -> usize {
+/// The number of distinct adapter chains that connect the outlet to the device.
+pub fn find_num_arrangements(mut input: Vec<usize>) -> Result<usize> {
     input.sort_unstable();
 
-    let computer = *input.last().unwrap() + 3;
+    let computer = input.last().ok_or_else(|| eyre!("no adapters"))? + 3;
 
     let mut num_arrangements_at_point = HashMap::new();
     num_arrangements_at_point.insert(computer, 1usize);
@@ -122,7 +137,7 @@ fn find_num_arrangements(mut input: Vec<usize>) -> usize {
         );
     });
 
-    get_num_arrangements(0, &num_arrangements_at_point)
+    Ok(get_num_arrangements(0, &num_arrangements_at_point))
 }
 
 #[cfg(test)]
@@ -130,6 +145,7 @@ mod tests {
     use std::time::Instant;
 
     use super::*;
+    use crate::generate;
     use eyre::Result;
 
     #[test]
@@ -172,12 +188,32 @@ mod tests {
         for (input, want) in tests {
             let parsed = parse_input(input)?;
             let now = Instant::now();
-            let got = find_num_arrangements(parsed);
+            let got = find_num_arrangements(parsed)?;
             let elapsed_ms = now.elapsed().as_millis();
             debug!(?got, ?elapsed_ms);
             assert_eq!(got, want);
         }
 
+        assert!(find_num_arrangements(parse_input("")?).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_num_arrangements_generated() -> Result<()> {
+        // the slow version walks every arrangement, so the inputs have to stay small
+        for seed in 0..20 {
+            let input = generate::generate(10, Some(25), seed)?;
+            let parsed = parse_input(&input)?;
+
+            assert_eq!(
+                find_num_arrangements(parsed.clone())?,
+                find_num_arrangements_sloooooow(parsed)?,
+                "seed {}",
+                seed
+            );
+        }
+
         Ok(())
     }
 }
@@ -1,91 +1,100 @@
-use eyre::{bail, eyre, Result};
-use std::{convert::TryFrom, time::Instant};
-use tracing::info;
+//! Day 11: Seating System, simulate people taking and leaving seats.
+
+use crate::{
+    grid::{Grid, DIRECTIONS_8},
+    solution::{Part, Solution},
+    visualize::Frame,
+};
+use eyre::{Result, WrapErr};
+use std::fmt;
 
 #[cfg(test)]
 use tracing::debug;
 
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day11.txt");
-    let waiting_area = WaitingArea::parse(input)?;
+/// Counts the occupied seats once the seating stabilizes, with the rules of
+/// [`Part1`] and then [`Part2`].
+pub struct Day11;
 
-    Part1::run(waiting_area.clone());
-    Part2::run(waiting_area);
+impl Solution for Day11 {
+    type Input = WaitingArea;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        WaitingArea::parse(input)
+    }
 
-    Ok(())
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(Part1::run(input.clone()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(Part2::run(input.clone()))
+    }
 }
 
+/// The seat layout of the waiting area.
 #[derive(Debug, Clone, PartialEq)]
-struct WaitingArea {
-    seat_layout: Vec<Vec<SeatState>>,
-    width: usize,
-    height: usize,
-    max_side: isize,
+pub struct WaitingArea {
+    seat_layout: Grid<SeatState>,
 }
 
 impl WaitingArea {
-    fn count_occupied(&self) -> usize {
+    pub fn count_occupied(&self) -> usize {
         self.seat_layout
             .iter()
-            .cloned()
-            .map(|row| {
-                row.iter()
-                    .cloned()
-                    .filter(|&seat| seat == SeatState::Occupied)
-                    .count()
-            })
-            .sum()
+            .filter(|&&seat| seat == SeatState::Occupied)
+            .count()
     }
 
-    fn parse(input: &str) -> Result<Self> {
-        let seat_layout = input
-            .trim()
-            .lines()
-            .map(SeatState::parse_line)
-            .collect::<Result<Vec<_>>>()?;
-
-        let height = seat_layout.len();
-        let width = seat_layout
-            .first()
-            .ok_or_else(|| eyre!("no rows in layout!"))?
-            .len();
-
-        Ok(Self {
-            seat_layout,
-            width,
-            height,
-            max_side: width.max(height) as isize,
-        })
+    /// Parses one row of `L`, `#` and `.` per line.
+    pub fn parse(input: &str) -> Result<Self> {
+        let seat_layout = Grid::parse(input, SeatState::parse).wrap_err("invalid seat layout")?;
+
+        Ok(Self { seat_layout })
     }
 
-    fn get_seat_state(&self, x: usize, y: usize) -> SeatState {
-        // debug!(x, y);
-        if x >= self.width || y >= self.height {
-            SeatState::Unavailable
-        } else if let Some(&seat) = self
-            .seat_layout
-            .get(y as usize)
-            .and_then(|row| row.get(x as usize))
-        {
-            seat
-        } else {
-            panic!(
-                "invalid seat layout! no seat for x: {}, y: {} with width: {}, height: {}",
-                x, y, self.width, self.height
-            )
-        }
+    /// The state of the seat at `x`, `y`, everything outside of the layout is floor.
+    pub fn get_seat_state(&self, x: usize, y: usize) -> SeatState {
+        self.seat_layout
+            .get(x, y)
+            .cloned()
+            .unwrap_or(SeatState::Unavailable)
     }
 
     #[cfg(test)]
     fn debug(&self) {
-        debug!(?self.width, ?self.height);
-        for line in &self.seat_layout {
-            debug!(?line);
-        }
+        debug!("\n{}", self.seat_layout);
     }
 }
 
-struct Part1;
+/// The layout after each round until it stops changing, with the rules of [`Part1`] or
+/// [`Part2`].
+pub fn frames(input: &str, part: Part) -> Result<Vec<Frame>> {
+    let waiting_area = WaitingArea::parse(input)?;
+    let rounds = match part {
+        Part::One => Part1::rounds(waiting_area),
+        Part::Two => Part2::rounds(waiting_area),
+    };
+
+    Ok(rounds
+        .iter()
+        .enumerate()
+        .map(|(round, waiting_area)| {
+            Frame::new(
+                format!(
+                    "round {}, {} occupied",
+                    round,
+                    waiting_area.count_occupied()
+                ),
+                waiting_area.seat_layout.to_string(),
+            )
+        })
+        .collect())
+}
+
+/// People look at the adjacent seats and leave when 4 or more are occupied.
+pub struct Part1;
 
 impl SeatingSystem for Part1 {
     fn num_occupied_needed_for_empty() -> usize {
@@ -99,17 +108,16 @@ impl SeatingSystem for Part1 {
         delta_x: isize,
         delta_y: isize,
     ) -> SeatState {
-        let x = usize::try_from(pos_x as isize + delta_x);
-        let y = usize::try_from(pos_y as isize + delta_y);
-
-        match (x, y) {
-            (Ok(x), Ok(y)) => waiting_area.get_seat_state(x, y),
-            _ => SeatState::Unavailable, // outside map
-        }
+        waiting_area
+            .seat_layout
+            .get_signed(pos_x as isize + delta_x, pos_y as isize + delta_y)
+            .cloned()
+            .unwrap_or(SeatState::Unavailable)
     }
 }
 
-struct Part2;
+/// People look at the first seat in each direction and leave when 5 or more are occupied.
+pub struct Part2;
 
 impl SeatingSystem for Part2 {
     fn num_occupied_needed_for_empty() -> usize {
@@ -123,26 +131,17 @@ impl SeatingSystem for Part2 {
         delta_x: isize,
         delta_y: isize,
     ) -> SeatState {
-        for i in 1..waiting_area.max_side {
-            let x = usize::try_from(pos_x as isize + (i * delta_x));
-            let y = usize::try_from(pos_y as isize + (i * delta_y));
-
-            let (x, y) = match (x, y) {
-                (Ok(x), Ok(y)) => (x, y),
-                _ => return SeatState::Unavailable, // outside map
-            };
-
-            let current = waiting_area.get_seat_state(x, y);
-            if current != SeatState::Unavailable {
-                return current;
-            }
-        }
-
-        SeatState::Unavailable
+        waiting_area
+            .seat_layout
+            .ray(pos_x, pos_y, delta_x, delta_y)
+            .map(|(x, y)| waiting_area.get_seat_state(x, y))
+            .find(|&seat| seat != SeatState::Unavailable)
+            .unwrap_or(SeatState::Unavailable)
     }
 }
 
-trait SeatingSystem {
+/// The rules people follow, [`SeatingSystem::run`] applies them until nothing changes.
+pub trait SeatingSystem {
     fn num_occupied_needed_for_empty() -> usize;
 
     fn look_in_direction(
@@ -175,24 +174,10 @@ trait SeatingSystem {
             return SeatState::Unavailable;
         }
 
-        let mut occupied: usize = 0;
-        occupied += Self::occupied_in_direction(waiting_area, x, y, 1, 1);
-        occupied += Self::occupied_in_direction(waiting_area, x, y, 0, 1);
-        occupied += Self::occupied_in_direction(waiting_area, x, y, 1, 0);
-
-        if x > 0 {
-            occupied += Self::occupied_in_direction(waiting_area, x, y, -1, 0);
-            occupied += Self::occupied_in_direction(waiting_area, x, y, -1, 1);
-        }
-
-        if y > 0 {
-            occupied += Self::occupied_in_direction(waiting_area, x, y, 0, -1);
-            occupied += Self::occupied_in_direction(waiting_area, x, y, 1, -1);
-        }
-
-        if x > 0 && y > 0 {
-            occupied += Self::occupied_in_direction(waiting_area, x, y, -1, -1);
-        }
+        let occupied: usize = DIRECTIONS_8
+            .iter()
+            .map(|&(dx, dy)| Self::occupied_in_direction(waiting_area, x, y, dx, dy))
+            .sum();
 
         if current == SeatState::Empty && occupied == 0 {
             SeatState::Occupied
@@ -206,25 +191,16 @@ trait SeatingSystem {
     }
 
     fn step(waiting_area: &WaitingArea) -> WaitingArea {
-        let mut seat_layout = Vec::with_capacity(waiting_area.height);
-        for y in 0..waiting_area.height {
-            let mut row = Vec::with_capacity(waiting_area.width);
-            for x in 0..waiting_area.height {
-                row.push(Self::get_next_seat_state(waiting_area, x, y));
-            }
-            seat_layout.push(row);
-        }
+        let layout = &waiting_area.seat_layout;
 
         WaitingArea {
-            seat_layout,
-            width: waiting_area.width,
-            height: waiting_area.height,
-            max_side: waiting_area.max_side,
+            seat_layout: Grid::from_fn(layout.width(), layout.height(), |x, y| {
+                Self::get_next_seat_state(waiting_area, x, y)
+            }),
         }
     }
 
-    fn run(mut waiting_area: WaitingArea) {
-        let now = Instant::now();
+    fn run(mut waiting_area: WaitingArea) -> usize {
         for _ in 0..1000 {
             let next = Self::step(&waiting_area);
 
@@ -234,22 +210,34 @@ trait SeatingSystem {
                 waiting_area = next
             }
         }
-        let elapsed_ms = now.elapsed().as_millis();
+        waiting_area.count_occupied()
+    }
 
-        let occupied = waiting_area.count_occupied();
-        info!(occupied, ?elapsed_ms);
+    /// The starting layout and the layout after every round that changed it.
+    fn rounds(waiting_area: WaitingArea) -> Vec<WaitingArea> {
+        let mut rounds = vec![waiting_area];
+        for _ in 0..1000 {
+            let next = Self::step(rounds.last().expect("the starting layout"));
+
+            if Some(&next) == rounds.last() {
+                break;
+            } else {
+                rounds.push(next)
+            }
+        }
+        rounds
     }
 }
 
 #[derive(Clone, Copy, PartialEq)]
-enum SeatState {
+pub enum SeatState {
     Empty,
     Occupied,
     Unavailable,
 }
 
-impl std::fmt::Debug for SeatState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for SeatState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             SeatState::Empty => write!(f, "L"),
             SeatState::Occupied => write!(f, "#"),
@@ -258,21 +246,19 @@ impl std::fmt::Debug for SeatState {
     }
 }
 
-impl SeatState {
-    fn parse_line(line: &str) -> Result<Vec<SeatState>> {
-        line.chars().map(SeatState::try_from).collect()
+impl fmt::Debug for SeatState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
     }
 }
 
-impl TryFrom<char> for SeatState {
-    type Error = eyre::Report;
-
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            'L' => Ok(SeatState::Empty),
-            '#' => Ok(SeatState::Occupied),
-            '.' => Ok(SeatState::Unavailable),
-            _ => bail!("invalid input: '{}'", value),
+impl SeatState {
+    fn parse(c: char) -> Option<Self> {
+        match c {
+            'L' => Some(SeatState::Empty),
+            '#' => Some(SeatState::Occupied),
+            '.' => Some(SeatState::Unavailable),
+            _ => None,
         }
     }
 }
@@ -1,35 +1,45 @@
+//! Day 12: Rain Risk, navigate the ferry through the storm.
+
+use crate::solution::Solution;
 use eyre::Result;
 use std::{num::ParseIntError, ops::AddAssign, str::FromStr};
 use thiserror::Error;
-use tracing::info;
 
 #[cfg(test)]
 use tracing::debug;
 
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day12.txt");
-    let program = Program::parse(input)?;
-    let mut ship = Part1ShipComputer::new();
+/// The manhattan distance the ship travels when steering the ship itself, and then when
+/// steering by a waypoint.
+pub struct Day12;
 
-    ship.run(&program.instructions);
+impl Solution for Day12 {
+    type Input = Program;
+    type Answer1 = isize;
+    type Answer2 = isize;
 
-    let distance = ship.get_distance();
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(Program::parse(input)?)
+    }
 
-    info!(distance);
+    fn part_1(&self, program: &Self::Input) -> Result<Self::Answer1> {
+        let mut ship = Part1ShipComputer::new();
 
-    let mut ship_2 = Part2ShipComputer::new();
+        ship.run(&program.instructions);
 
-    ship_2.run(&program.instructions);
+        Ok(ship.get_distance())
+    }
 
-    let distance_2 = ship_2.get_distance();
+    fn part_2(&self, program: &Self::Input) -> Result<Self::Answer2> {
+        let mut ship_2 = Part2ShipComputer::new();
 
-    info!(distance_2);
+        ship_2.run(&program.instructions);
 
-    Ok(())
+        Ok(ship_2.get_distance())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum Facing {
+pub enum Facing {
     North,
     South,
     West,
@@ -37,7 +47,7 @@ enum Facing {
 }
 
 impl Facing {
-    fn turn_right(self) -> Self {
+    pub fn turn_right(self) -> Self {
         match self {
             Facing::North => Facing::East,
             Facing::East => Facing::South,
@@ -46,7 +56,7 @@ impl Facing {
         }
     }
 
-    fn turn_left(self) -> Self {
+    pub fn turn_left(self) -> Self {
         match self {
             Facing::North => Facing::West,
             Facing::East => Facing::North,
@@ -56,15 +66,23 @@ impl Facing {
     }
 }
 
+/// Moves the ship itself, `F` moves it in the direction it is facing.
 #[derive(Debug, Clone, PartialEq)]
-struct Part1ShipComputer {
+pub struct Part1ShipComputer {
     facing: Facing,
     x: isize,
     y: isize,
 }
 
+impl Default for Part1ShipComputer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Part1ShipComputer {
-    fn new() -> Self {
+    /// A ship at the origin facing east.
+    pub fn new() -> Self {
         Self {
             facing: Facing::East,
             x: 0,
@@ -72,7 +90,7 @@ impl Part1ShipComputer {
         }
     }
 
-    fn run(&mut self, instructions: &[Instruction]) {
+    pub fn run(&mut self, instructions: &[Instruction]) {
         for instruction in instructions.iter().cloned() {
             match instruction {
                 Instruction::North(n) => self.y += n as isize,
@@ -99,26 +117,30 @@ impl Part1ShipComputer {
         }
     }
 
-    fn get_distance(&self) -> isize {
+    /// The manhattan distance from the origin.
+    pub fn get_distance(&self) -> isize {
         self.x.abs() + self.y.abs()
     }
 }
 
+/// A position, north and east are positive.
 #[derive(Debug, Clone, Copy, PartialEq)]
-struct Point {
-    x: isize,
-    y: isize,
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
 }
 
 impl Point {
-    fn rotate_left(self) -> Self {
+    /// Rotates 90 degrees counter clockwise around the origin.
+    pub fn rotate_left(self) -> Self {
         Self {
             x: -self.y,
             y: self.x,
         }
     }
 
-    fn rotate_right(self) -> Self {
+    /// Rotates 90 degrees clockwise around the origin.
+    pub fn rotate_right(self) -> Self {
         Self {
             x: self.y,
             y: -self.x,
@@ -133,15 +155,23 @@ impl AddAssign for Point {
     }
 }
 
+/// Moves the waypoint, `F` moves the ship to the waypoint a number of times.
 #[derive(Debug, Clone, PartialEq)]
-struct Part2ShipComputer {
+pub struct Part2ShipComputer {
     facing: Facing,
     pos: Point,
     waypoint: Point,
 }
 
+impl Default for Part2ShipComputer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Part2ShipComputer {
-    fn new() -> Self {
+    /// A ship at the origin with the waypoint 10 east and 1 north of it.
+    pub fn new() -> Self {
         Self {
             facing: Facing::East,
             pos: Point { x: 0, y: 0 },
@@ -149,7 +179,7 @@ impl Part2ShipComputer {
         }
     }
 
-    fn run(&mut self, instructions: &[Instruction]) {
+    pub fn run(&mut self, instructions: &[Instruction]) {
         for instruction in instructions.iter().cloned() {
             match instruction {
                 Instruction::North(n) => self.waypoint.y += n as isize,
@@ -175,18 +205,21 @@ impl Part2ShipComputer {
         }
     }
 
-    fn get_distance(&self) -> isize {
+    /// The manhattan distance from the origin.
+    pub fn get_distance(&self) -> isize {
         self.pos.x.abs() + self.pos.y.abs()
     }
 }
 
+/// The navigation instructions.
 #[derive(Debug, Clone, PartialEq)]
-struct Program {
+pub struct Program {
     instructions: Vec<Instruction>,
 }
 
 impl Program {
-    fn parse(input: &str) -> Result<Self, InstructionParseError> {
+    /// Parses one instruction, like `F10` or `R90`, per line.
+    pub fn parse(input: &str) -> Result<Self, InstructionParseError> {
         let instructions = input
             .lines()
             .map(Instruction::from_str)
@@ -194,18 +227,23 @@ impl Program {
 
         Ok(Self { instructions })
     }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
 }
 
 #[derive(Error, Debug)]
-enum InstructionParseError {
+pub enum InstructionParseError {
     #[error("invalid instruction: '{0}'")]
     InvalidInstruction(String),
     #[error("invalid count")]
     InvalidCount(#[from] ParseIntError),
 }
 
+/// A navigation instruction, turns are counted in quarter turns.
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     North(usize),
     East(usize),
     South(usize),
@@ -1,44 +1,84 @@
+//! Day 13: Shuttle Search, find the bus to the airport.
+
+use crate::solution::Solution;
 use eyre::{eyre, Result};
 use std::{collections::HashMap, str::FromStr};
-use tracing::{debug, info};
+use tracing::debug;
+
+/// Finds the earliest bus times the wait, and then the earliest timestamp where every bus
+/// departs at its offset.
+pub struct Day13;
 
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day13.txt");
-    let shedule = Schedule::parse(input)?;
-    shedule.find_bus();
+impl Solution for Day13 {
+    type Input = Schedule;
+    type Answer1 = usize;
+    type Answer2 = isize;
 
-    win_contest(input)?;
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Schedule::parse(input)
+    }
+
+    fn part_1(&self, schedule: &Self::Input) -> Result<Self::Answer1> {
+        schedule.find_bus()
+    }
 
-    Ok(())
+    fn part_2(&self, schedule: &Self::Input) -> Result<Self::Answer2> {
+        win_contest(&schedule.offsets)
+    }
 }
 
+/// The earliest time we can leave and the buses in service.
 #[derive(Debug, Clone, PartialEq)]
-struct Schedule {
+pub struct Schedule {
     ttl: usize, // time to leave
     bus_ids: Vec<usize>,
+    offsets: Vec<(isize, isize)>, // (bus id, offset)
 }
 
 impl Schedule {
-    fn parse(input: &str) -> Result<Self> {
+    /// Parses the time to leave on the first line and the comma separated bus ids, `x` for
+    /// out of service, on the second.
+    pub fn parse(input: &str) -> Result<Self> {
         let mut lines = input.lines();
         let ttl: usize = lines
             .next()
             .ok_or_else(|| eyre!("invalid input: no lines"))?
             .parse()?;
 
-        let bus_ids = lines
+        let line = lines
             .next()
-            .ok_or_else(|| eyre!("invalid input: not enough lines"))?
+            .ok_or_else(|| eyre!("invalid input: not enough lines"))?;
+
+        let bus_ids = line
             .split(',')
             .filter(|&s| s != "x")
             .inspect(|s| debug!(?s))
             .map(usize::from_str)
             .collect::<Result<Vec<usize>, _>>()?;
 
-        Ok(Self { ttl, bus_ids })
+        let offsets = line
+            .split(',')
+            .enumerate()
+            .filter(|(_, s)| *s != "x")
+            .inspect(|(offset, s)| debug!(offset, ?s))
+            .map(|(offset, s)| isize::from_str(s).map(|s| (s, offset as isize)))
+            .collect::<Result<Vec<(isize, isize)>, _>>()?;
+
+        Ok(Self {
+            ttl,
+            bus_ids,
+            offsets,
+        })
+    }
+
+    /// The `(bus id, offset)` of every bus in service, the offset is its position in the
+    /// list.
+    pub fn offsets(&self) -> &[(isize, isize)] {
+        &self.offsets
     }
 
-    fn find_bus(&self) {
+    /// The id of the earliest bus we can take times the minutes we wait for it.
+    pub fn find_bus(&self) -> Result<usize> {
         let ttl = self.ttl;
         let omg = self
             .bus_ids
@@ -52,30 +92,20 @@ impl Schedule {
             })
             .collect::<HashMap<_, _>>();
 
-        let min = omg.keys().min().unwrap();
+        let min = omg
+            .keys()
+            .min()
+            .ok_or_else(|| eyre!("invalid input: no bus ids"))?;
 
         let id = omg.get(min).unwrap();
 
-        let answer = min * id;
-
-        info!(?answer);
+        Ok(min * id)
     }
 }
 
-fn win_contest(input: &str) -> Result<()> {
-    let mut lines = input.lines();
-    let _ = lines.next(); // skip first line
-
-    let mut bus_ids = lines
-        .next()
-        .ok_or_else(|| eyre!("invalid input: not enough lines"))?
-        .split(',')
-        .enumerate()
-        .filter(|(_, s)| *s != "x")
-        .inspect(|(offset, s)| debug!(offset, ?s))
-        .map(|(offset, s)| isize::from_str(s).map(|s| (s, offset as isize)))
-        .collect::<Result<Vec<(isize, isize)>, _>>()?;
-
+/// The earliest timestamp where every bus departs `offset` minutes after it.
+pub fn win_contest(bus_ids: &[(isize, isize)]) -> Result<isize> {
+    let mut bus_ids = bus_ids.to_vec();
     bus_ids.sort_unstable();
 
     let mut seen_ids = Vec::new();
@@ -113,11 +143,7 @@ fn win_contest(input: &str) -> Result<()> {
         }
     }
 
-    let candidate = start;
-
-    info!(candidate);
-
-    Ok(())
+    Ok(start)
 }
 
 #[cfg(test)]
@@ -145,7 +171,7 @@ mod tests {
 
         debug!(?parsed);
 
-        parsed.find_bus();
+        assert_eq!(parsed.find_bus()?, 295);
 
         Ok(())
     }
@@ -153,6 +179,10 @@ mod tests {
     #[test]
     fn test_henke() -> Result<()> {
         let input = include_str!("../data/day13_test.txt");
-        win_contest(input)
+        let schedule = Schedule::parse(input)?;
+
+        assert_eq!(win_contest(&schedule.offsets)?, 1068781);
+
+        Ok(())
     }
 }
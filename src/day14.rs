@@ -1,21 +1,38 @@
-use eyre::{bail, eyre, Result};
-use std::{collections::HashMap, str::FromStr};
-use tracing::{debug, info, instrument, trace};
-
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day14.txt");
-    let program = Program::parse(input)?;
-    let mut computer_1 = ComputerPart1::default();
-    computer_1.run(&program)?;
+//! Day 14: Docking Data, run the initialization program of the sea port computer.
+
+use crate::{
+    parse::{ParseError, Span, WithSnippet},
+    solution::Solution,
+};
+use eyre::Result;
+use std::collections::HashMap;
+use tracing::{debug, instrument, trace};
+
+/// Sums the memory after running the program with the value decoder, and then with the
+/// memory address decoder.
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Program;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Program::parse(input)
+    }
 
-    let mut computer_2 = ComputerPart2::default();
-    computer_2.run(&program)?;
+    fn part_1(&self, program: &Self::Input) -> Result<Self::Answer1> {
+        ComputerPart1::default().run(program)
+    }
 
-    Ok(())
+    fn part_2(&self, program: &Self::Input) -> Result<Self::Answer2> {
+        ComputerPart2::default().run(program)
+    }
 }
 
+/// An instruction, the mask is split into one bit mask for each of `1`, `0` and `X`.
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     SetMask {
         ones_mask: u64,
         zeroes_mask: u64,
@@ -27,82 +44,79 @@ enum Instruction {
     },
 }
 
+impl Instruction {
+    fn from_span(line: Span) -> Result<Self, ParseError> {
+        let (target, value) = line.split_once(" = ")?;
+
+        if target.as_str() == "mask" {
+            debug!("mask:   {}", value.as_str());
+            let (mut ones_mask, mut zeroes_mask, mut x_mask) = (0u64, 0u64, 0u64);
+            for (i, c) in value.as_str().char_indices() {
+                ones_mask <<= 1;
+                zeroes_mask <<= 1;
+                x_mask <<= 1;
+                match c {
+                    '1' => ones_mask += 1,
+                    '0' => zeroes_mask += 1,
+                    'X' => x_mask += 1,
+                    _ => {
+                        return Err(value
+                            .slice(i..i + c.len_utf8())
+                            .error_other(format!("invalid mask bit: '{}'", c)))
+                    }
+                }
+            }
+            debug!("zeroes: {:036b}", zeroes_mask);
+            debug!("ones:   {:036b}", ones_mask);
+            debug!("x:      {:036b}", x_mask);
+            return Ok(Instruction::SetMask {
+                ones_mask,
+                zeroes_mask,
+                x_mask,
+            });
+        }
+
+        let addr = target.strip_prefix("mem[")?.strip_suffix("]")?.parse()?;
+        let value = value.parse()?;
+        Ok(Instruction::SetMemory { addr, value })
+    }
+}
+
+/// The initialization program.
 #[derive(Debug, Clone, PartialEq)]
-struct Program {
+pub struct Program {
     instructions: Vec<Instruction>,
 }
 
 impl Program {
+    /// Parses one `mask = ...` or `mem[addr] = value` instruction per line.
     #[instrument(skip(input))]
-    fn parse(input: &str) -> Result<Self> {
-        let instructions = input
+    pub fn parse(input: &str) -> Result<Self> {
+        let instructions = Span::new(input)
             .lines()
-            .map(|line| {
-                let mut split = line.split(" = ");
-
-                let first = split.next().ok_or_else(|| {
-                    eyre!("invalid input, first split should always succeed: {}", line)
-                })?;
-                let second = split.next().ok_or_else(|| {
-                    eyre!(
-                        "invalid input, should be possible to split on ' = ': {}",
-                        line
-                    )
-                })?;
-
-                if first == "mask" {
-                    debug!("mask:   {}", second);
-                    let (ones_mask, zeroes_mask, x_mask) = second.chars().try_fold(
-                        (0u64, 0u64, 0u64),
-                        |(mut ones_mask, mut zeroes_mask, mut x_mask), c| {
-                            ones_mask <<= 1;
-                            zeroes_mask <<= 1;
-                            x_mask <<= 1;
-                            if c == '1' {
-                                ones_mask += 1;
-                            } else if c == '0' {
-                                zeroes_mask += 1;
-                            } else if c == 'X' {
-                                x_mask += 1;
-                            } else {
-                                bail!("invalid input, bad mask: {}", line);
-                            }
-                            Ok((ones_mask, zeroes_mask, x_mask))
-                        },
-                    )?;
-                    debug!("zeroes: {:036b}", zeroes_mask);
-                    debug!("ones:   {:036b}", ones_mask);
-                    debug!("x:      {:036b}", x_mask);
-                    return Ok(Instruction::SetMask {
-                        ones_mask,
-                        zeroes_mask,
-                        x_mask,
-                    });
-                } else if let Some(first) =
-                    first.strip_prefix("mem[").and_then(|f| f.strip_suffix("]"))
-                {
-                    let addr = u64::from_str(first)?;
-                    let value = u64::from_str(second)?;
-                    return Ok(Instruction::SetMemory { addr, value });
-                }
-
-                bail!("invalid input!: {}", line)
-            })
-            .collect::<Result<Vec<_>>>()?;
+            .map(Instruction::from_span)
+            .collect::<Result<Vec<_>, _>>()
+            .with_snippet()?;
         Ok(Self { instructions })
     }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
 }
 
+/// The version 1 decoder chip, the mask is applied to the values.
 #[derive(Debug, Clone, PartialEq, Default)]
-struct ComputerPart1 {
+pub struct ComputerPart1 {
     memory: HashMap<u64, u64>,
     and_mask: u64,
     or_mask: u64,
 }
 
 impl ComputerPart1 {
+    /// Runs the program and returns the sum of the memory.
     #[instrument(skip(self, program))]
-    fn run(&mut self, program: &Program) -> Result<u64> {
+    pub fn run(&mut self, program: &Program) -> Result<u64> {
         for instrucion in program.instructions.iter().cloned() {
             match instrucion {
                 Instruction::SetMask {
@@ -129,14 +143,16 @@ impl ComputerPart1 {
 
         let sum: u64 = self.memory.values().sum();
 
-        info!(sum);
+        debug!(sum);
 
         Ok(sum)
     }
 }
 
+/// The version 2 decoder chip, the mask is applied to the addresses and every `X` is both
+/// a 0 and a 1.
 #[derive(Debug, Clone, PartialEq, Default)]
-struct ComputerPart2 {
+pub struct ComputerPart2 {
     memory: HashMap<u64, u64>,
     ones_mask: u64,
     flipped_x_mask: u64,
@@ -144,8 +160,9 @@ struct ComputerPart2 {
 }
 
 impl ComputerPart2 {
+    /// Runs the program and returns the sum of the memory.
     #[instrument(skip(self, program))]
-    fn run(&mut self, program: &Program) -> Result<u64> {
+    pub fn run(&mut self, program: &Program) -> Result<u64> {
         for instruction in program.instructions.iter().cloned() {
             debug!(?instruction);
             match instruction {
@@ -194,7 +211,7 @@ impl ComputerPart2 {
                         debug!("i:      {:036b}", i);
                         debug!("x_mask: {:036b}", x_mask);
                         let mut mask = 0u64;
-                        let mut i = i as u64;
+                        let mut i = i;
                         for shift in shifts.iter().cloned().rev() {
                             mask += i & 1;
                             mask <<= shift;
@@ -229,7 +246,7 @@ impl ComputerPart2 {
 
         let sum: u64 = self.memory.values().sum();
 
-        info!(sum);
+        debug!(sum);
 
         Ok(sum)
     }
@@ -251,6 +268,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_invalid() {
+        let tests = vec![
+            (
+                "mask = XX1\nmem[8] 11",
+                "invalid input on line 2, column 1: expected ' = ', found 'mem[8] 11'",
+            ),
+            (
+                "mask = X21",
+                "invalid input on line 1, column 9: invalid mask bit: '2'",
+            ),
+            (
+                "mem[a] = 11",
+                "invalid input on line 1, column 5: invalid value: 'a', invalid digit found in string",
+            ),
+            (
+                "mom[8] = 11",
+                "invalid input on line 1, column 1: expected 'mem[', found 'mom[8]'",
+            ),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(format!("{:#}", Program::parse(input).unwrap_err()), want);
+        }
+    }
+
     #[test]
     fn test_run() -> Result<()> {
         let input = include_str!("../data/day14_test.txt");
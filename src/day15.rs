@@ -1,20 +1,33 @@
+//! Day 15: Rambunctious Recitation, play the elves' memory game.
+
+use crate::solution::Solution;
 use eyre::Result;
 use std::{collections::HashMap, str::FromStr};
-use tracing::{debug, info, instrument};
-
-#[instrument]
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day15.txt");
-    let nums = parse(input)?;
-    let answer = memory_game(nums.clone(), 2020);
-    info!(answer);
-    let answer_2 = memory_game_2(nums, 30000000);
-    info!(answer_2);
-
-    Ok(())
+use tracing::debug;
+
+/// The 2020th, and then the 30000000th, number spoken in the memory game.
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_1(&self, nums: &Self::Input) -> Result<Self::Answer1> {
+        Ok(memory_game(nums.clone(), 2020))
+    }
+
+    fn part_2(&self, nums: &Self::Input) -> Result<Self::Answer2> {
+        Ok(memory_game_2(nums.clone(), 30000000))
+    }
 }
 
-fn parse(input: &str) -> Result<Vec<usize>> {
+/// Parses the comma separated starting numbers.
+pub fn parse(input: &str) -> Result<Vec<usize>> {
     input
         .trim()
         .split(',')
@@ -23,7 +36,8 @@ fn parse(input: &str) -> Result<Vec<usize>> {
         .map_err(eyre::Report::new)
 }
 
-fn memory_game(mut nums: Vec<usize>, end: usize) -> usize {
+/// The `end`th number spoken, by searching back through every spoken number.
+pub fn memory_game(mut nums: Vec<usize>, end: usize) -> usize {
     let start = nums.len();
 
     for _ in start..end {
@@ -41,7 +55,8 @@ fn memory_game(mut nums: Vec<usize>, end: usize) -> usize {
     *nums.last().unwrap()
 }
 
-fn memory_game_2(mut nums: Vec<usize>, end: usize) -> usize {
+/// The `end`th number spoken, by remembering when each number was last spoken.
+pub fn memory_game_2(mut nums: Vec<usize>, end: usize) -> usize {
     debug!(?nums);
     let mut current = nums.pop().unwrap();
     let start = nums.len();
@@ -73,6 +88,7 @@ fn memory_game_2(mut nums: Vec<usize>, end: usize) -> usize {
 mod tests {
 
     use super::*;
+    use crate::generate;
     use eyre::Result;
 
     #[test]
@@ -124,4 +140,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_memory_game_generated() -> Result<()> {
+        for size in 1..=8 {
+            for seed in 0..5 {
+                let nums = parse(&generate::generate(15, Some(size), seed)?)?;
+
+                for &end in [size, 10, 2020].iter() {
+                    assert_eq!(
+                        memory_game(nums.clone(), end),
+                        memory_game_2(nums.clone(), end),
+                        "{:?}, end {}",
+                        nums,
+                        end
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,34 +1,52 @@
-use eyre::{eyre, Result};
+//! Day 16: Ticket Translation, work out which field is which on the train tickets.
+
+use crate::{
+    parse::{self, ParseError, Span, WithSnippet},
+    solution::Solution,
+};
+use eyre::Result;
 use std::{
     collections::{HashMap, HashSet},
     ops::RangeInclusive,
     str::FromStr,
-    time::Instant,
 };
-use tracing::{debug, info, instrument};
+use tracing::debug;
+
+/// Sums the values that are not valid for any field, and then multiplies the departure
+/// fields of our ticket.
+pub struct Day16;
 
-#[instrument]
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day16.txt");
-    let mut scanner = TicketScanner::parse(input)?;
-    let answer: usize = scanner.find_invalid_values().iter().sum();
-    info!(?answer);
+impl Solution for Day16 {
+    type Input = TicketScanner;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    let now = Instant::now();
-    let answer_2 = scanner.get_departure_value();
-    let elapsed_micros = now.elapsed().as_micros();
-    info!(?answer_2, ?elapsed_micros);
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        TicketScanner::parse(input)
+    }
+
+    fn part_1(&self, scanner: &Self::Input) -> Result<Self::Answer1> {
+        Ok(scanner.find_invalid_values().iter().sum())
+    }
 
-    Ok(())
+    fn part_2(&self, scanner: &Self::Input) -> Result<Self::Answer2> {
+        Ok(scanner.clone().get_departure_value())
+    }
 }
 
+/// The comma separated field values of a ticket.
 #[derive(Debug, Clone, PartialEq)]
-struct Ticket {
+pub struct Ticket {
     values: Vec<usize>,
 }
 
 impl Ticket {
-    fn has_invalid_value(&self, rules: &[Rule]) -> Option<usize> {
+    pub fn values(&self) -> &[usize] {
+        &self.values
+    }
+
+    /// The first value that is not valid for any of the rules.
+    pub fn has_invalid_value(&self, rules: &[Rule]) -> Option<usize> {
         for value in self.values.iter() {
             if !rules.iter().any(|rule| rule.is_valid_value(value)) {
                 return Some(*value);
@@ -38,117 +56,104 @@ impl Ticket {
     }
 }
 
+impl Ticket {
+    fn from_span(line: Span) -> Result<Self, ParseError> {
+        Ok(Self {
+            values: line.parse_list(",")?,
+        })
+    }
+}
+
 impl FromStr for Ticket {
     type Err = eyre::Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let values = s
-            .split(',')
-            .map(usize::from_str)
-            .collect::<Result<Vec<_>, _>>()?;
-        Ok(Self { values })
+        Self::from_span(Span::new(s)).with_snippet()
     }
 }
 
+/// A field and its two valid ranges, `<name>: <a>-<b> or <c>-<d>`.
 #[derive(Debug, Clone, PartialEq)]
-struct Rule {
+pub struct Rule {
     name: String,
     range_a: RangeInclusive<usize>,
     range_b: RangeInclusive<usize>,
 }
 
 impl Rule {
-    fn is_valid_value(&self, value: &usize) -> bool {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_valid_value(&self, value: &usize) -> bool {
         self.range_a.contains(value) || self.range_b.contains(value)
     }
 
-    fn is_valid_for_all_tickets_at_index(&self, tickets: &[Ticket], index: usize) -> bool {
+    pub fn is_valid_for_all_tickets_at_index(&self, tickets: &[Ticket], index: usize) -> bool {
         tickets
             .iter()
             .all(|ticket| self.is_valid_value(&ticket.values[index]))
     }
 }
 
-impl FromStr for Rule {
-    type Err = eyre::Report;
+fn parse_range(range: Span) -> Result<RangeInclusive<usize>, ParseError> {
+    let (start, end) = range.split_once("-")?;
+    Ok(start.parse()?..=end.parse()?)
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(": ");
-        let name = iter
-            .next()
-            .ok_or_else(|| eyre!("invalid input, no name: {}", s))?
-            .to_string();
-        let mut ranges = iter
-            .next()
-            .ok_or_else(|| eyre!("invalid input, no ranges: {}", s))?
-            .split(" or ");
-
-        let mut range_a_values = ranges
-            .next()
-            .ok_or_else(|| eyre!("invalid input, no first range: {}", s))?
-            .split('-')
-            .map(usize::from_str);
-
-        let range_a_start = range_a_values
-            .next()
-            .ok_or_else(|| eyre!("invalid input, no start number in first range, {}", s))??;
-        let range_a_end = range_a_values
-            .next()
-            .ok_or_else(|| eyre!("invalid input, no end number in first range, {}", s))??;
-
-        let mut range_b_values = ranges
-            .next()
-            .ok_or_else(|| eyre!("invalid input, no first range: {}", s))?
-            .split('-')
-            .map(usize::from_str);
-
-        let range_b_start = range_b_values
-            .next()
-            .ok_or_else(|| eyre!("invalid input, no start number in second range, {}", s))??;
-        let range_b_end = range_b_values
-            .next()
-            .ok_or_else(|| eyre!("invalid input, no end number in second range, {}", s))??;
+impl Rule {
+    fn from_span(line: Span) -> Result<Self, ParseError> {
+        let (name, ranges) = line.split_once(": ")?;
+        let (range_a, range_b) = ranges.split_once(" or ")?;
 
         Ok(Self {
-            name,
-            range_a: range_a_start..=range_a_end,
-            range_b: range_b_start..=range_b_end,
+            name: name.as_str().to_string(),
+            range_a: parse_range(range_a)?,
+            range_b: parse_range(range_b)?,
         })
     }
 }
 
+impl FromStr for Rule {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_span(Span::new(s)).with_snippet()
+    }
+}
+
+/// The rules, our ticket and the nearby tickets.
 #[derive(Debug, Clone, PartialEq)]
-struct TicketScanner {
+pub struct TicketScanner {
     rules: Vec<Rule>,
     my_ticket: Ticket,
     nearby_tickets: Vec<Ticket>,
 }
 
 impl TicketScanner {
-    fn parse(input: &str) -> Result<Self> {
-        let mut iter = input.split("\n\n");
-        let rules = iter
-            .next()
-            .ok_or_else(|| eyre!("invalid input, missing rules: {}", input))?
+    /// Parses the rules, our ticket and the nearby tickets, separated by blank lines.
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::from_span(Span::new(input)).with_snippet()
+    }
+
+    fn from_span(input: Span) -> Result<Self, ParseError> {
+        let mut sections = input.records();
+
+        let rules = parse::next(&mut sections, &input, "the rules")?
             .lines()
-            .map(Rule::from_str)
+            .map(Rule::from_span)
             .collect::<Result<Vec<_>, _>>()?;
 
-        let my_ticket = iter
-            .next()
-            .ok_or_else(|| eyre!("invalid ticket, missing my ticket section: {}", input))?
-            .lines()
-            .skip(1) // skip header
-            .map(Ticket::from_str)
-            .next()
-            .ok_or_else(|| eyre!("invalid ticket, missing my ticket: {}", input))??;
-
-        let nearby_tickets = iter
-            .next()
-            .ok_or_else(|| eyre!("invalid ticket, missing nearby tickets section: {}", input))?
-            .lines()
-            .skip(1) // skip header
-            .map(Ticket::from_str)
+        let section = parse::next(&mut sections, &input, "your ticket")?;
+        let mut lines = section.lines();
+        parse::next(&mut lines, &section, "'your ticket:'")?.strip_prefix("your ticket:")?;
+        let my_ticket = Ticket::from_span(parse::next(&mut lines, &section, "your ticket")?)?;
+
+        let section = parse::next(&mut sections, &input, "the nearby tickets")?;
+        let mut lines = section.lines();
+        parse::next(&mut lines, &section, "'nearby tickets:'")?.strip_prefix("nearby tickets:")?;
+        let nearby_tickets = lines
+            .map(Ticket::from_span)
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Self {
@@ -158,14 +163,21 @@ impl TicketScanner {
         })
     }
 
-    fn find_invalid_values(&self) -> Vec<usize> {
+    /// The rules, in field order once [`TicketScanner::sort_rules`] has been called.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// The first invalid value of each nearby ticket that has one.
+    pub fn find_invalid_values(&self) -> Vec<usize> {
         self.nearby_tickets
             .iter()
             .filter_map(|ticket| ticket.has_invalid_value(&self.rules))
             .collect()
     }
 
-    fn filter_valid_tickets(&mut self) {
+    /// Drops the nearby tickets that have an invalid value.
+    pub fn filter_valid_tickets(&mut self) {
         let tickets: Vec<Ticket> = self.nearby_tickets.drain(..).collect();
         self.nearby_tickets = tickets
             .into_iter()
@@ -173,10 +185,11 @@ impl TicketScanner {
             .collect();
     }
 
-    fn sort_rules(&mut self) {
+    /// Drops the invalid tickets and orders the rules to match the fields of the tickets.
+    pub fn sort_rules(&mut self) {
         self.filter_valid_tickets();
 
-        let mut value_indexes: HashSet<usize> = (0..self.rules.len()).into_iter().collect();
+        let mut value_indexes: HashSet<usize> = (0..self.rules.len()).collect();
         let mut rule_index_to_rule_map: HashMap<_, _> = self.rules.drain(..).enumerate().collect();
         let mut value_index_to_rule_index_map = HashMap::new();
 
@@ -207,7 +220,7 @@ impl TicketScanner {
             .into_iter()
             .collect::<Vec<_>>();
 
-        value_index_to_rule_index.sort_by(|a, b| a.0.cmp(&b.0));
+        value_index_to_rule_index.sort_by_key(|a| a.0);
 
         self.rules = value_index_to_rule_index
             .into_iter()
@@ -215,7 +228,8 @@ impl TicketScanner {
             .collect();
     }
 
-    fn get_departure_value(&mut self) -> usize {
+    /// Sorts the rules and multiplies the departure fields of our ticket.
+    pub fn get_departure_value(&mut self) -> usize {
         self.sort_rules();
 
         self.rules
@@ -251,6 +265,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_invalid() {
+        let tests = vec![
+            (
+                "class: 1-3 or 5\n\nyour ticket:\n7,1,14",
+                "invalid input on line 1, column 15: expected '-', found '5'",
+            ),
+            (
+                "class: 1-3 or 5-7\n\nyour ticket:\n7,x,14",
+                "invalid input on line 4, column 3: invalid value: 'x', invalid digit found in string",
+            ),
+            (
+                "class: 1-3 or 5-7\n\nyour ticket:\n7,1,14\n",
+                "invalid input on line 5, column 1: unexpected end of input, expected the nearby tickets",
+            ),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(
+                format!("{:#}", TicketScanner::parse(input).unwrap_err()),
+                want
+            );
+        }
+    }
+
     #[test]
     fn test_scanner() -> Result<()> {
         let input = include_str!("../data/day16_test.txt");
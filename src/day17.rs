@@ -1,56 +1,156 @@
-use eyre::Result;
+//! Day 17: Conway Cubes, boot the pocket dimension.
+
+use crate::{
+    grid::Grid,
+    solution::{Part, Solution},
+    visualize::{self, Frame},
+};
+use eyre::{ensure, Result, WrapErr};
 use std::{
     collections::{BTreeSet, HashSet},
     fmt::Debug,
-    time::Instant,
+    ops::RangeInclusive,
 };
-use tracing::{debug, info, instrument};
+use tracing::debug;
 
-#[instrument]
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day17.txt");
+/// Counts the active cubes after six cycles in three, and then four, dimensions.
+pub struct Day17;
 
-    let dimension = PocketDimension::parse(input)?;
-    debug!(?dimension);
+/// The same starting state in each of the dimension implementations.
+pub struct Dimensions {
+    pocket: PocketDimension,
+    hyper: HyperDimension,
+    hyper_i8: HyperDimensionI8,
+    hyper_i8_array: HyperDimensionI8Array,
+}
 
-    let active_cubes = dimension.boot();
-    info!(active_cubes);
+impl Solution for Day17 {
+    type Input = Dimensions;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(Dimensions {
+            pocket: PocketDimension::parse(input)?,
+            hyper: HyperDimension::parse(input)?,
+            hyper_i8: HyperDimensionI8::parse(input)?,
+            hyper_i8_array: HyperDimensionI8Array::parse(input)?,
+        })
+    }
 
-    let hyper_dimension = HyperDimension::parse(input)?;
-    debug!(?hyper_dimension);
+    fn part_1(&self, dimensions: &Self::Input) -> Result<Self::Answer1> {
+        let dimension = dimensions.pocket.clone();
+        debug!(?dimension);
 
-    let now = Instant::now();
-    let active_hyper_cubes = hyper_dimension.boot();
-    let elapsed_micros = now.elapsed().as_micros();
-    info!(active_hyper_cubes, ?elapsed_micros);
+        Ok(dimension.boot())
+    }
 
-    assert_eq!(active_hyper_cubes, 2696);
+    fn part_2(&self, dimensions: &Self::Input) -> Result<Self::Answer2> {
+        let hyper_dimension = dimensions.hyper.clone();
+        debug!(?hyper_dimension);
 
-    let hyper_dimension_i8 = HyperDimensionI8::parse(input)?;
-    debug!(?hyper_dimension_i8);
+        let active_hyper_cubes = hyper_dimension.boot();
+        debug!(active_hyper_cubes);
 
-    let now = Instant::now();
-    let active_hyper_cubes_i8 = hyper_dimension_i8.boot();
-    let elapsed_micros = now.elapsed().as_micros();
-    info!(active_hyper_cubes_i8, ?elapsed_micros);
+        let hyper_dimension_i8 = dimensions.hyper_i8.clone();
+        debug!(?hyper_dimension_i8);
 
-    assert_eq!(active_hyper_cubes_i8, 2696);
+        let active_hyper_cubes_i8 = hyper_dimension_i8.boot();
+        debug!(active_hyper_cubes_i8);
 
-    let hyper_dimension_i8_array = HyperDimensionI8Array::parse(input)?;
-    debug!(?hyper_dimension_i8_array);
+        let hyper_dimension_i8_array = dimensions.hyper_i8_array.clone();
+        debug!(?hyper_dimension_i8_array);
 
-    let now = Instant::now();
-    let active_hyper_cubes_i8_array = hyper_dimension_i8_array.boot();
-    let elapsed_micros = now.elapsed().as_micros();
-    info!(active_hyper_cubes_i8_array, ?elapsed_micros);
+        let active_hyper_cubes_i8_array = hyper_dimension_i8_array.boot();
+        debug!(active_hyper_cubes_i8_array);
 
-    assert_eq!(active_hyper_cubes_i8_array, 2696);
+        ensure!(
+            active_hyper_cubes == active_hyper_cubes_i8
+                && active_hyper_cubes == active_hyper_cubes_i8_array,
+            "the dimensions disagree: {} {} {}",
+            active_hyper_cubes,
+            active_hyper_cubes_i8,
+            active_hyper_cubes_i8_array
+        );
+
+        Ok(active_hyper_cubes)
+    }
+}
+
+/// The positions of the active cubes in the starting slice, `#` is active and `.`
+/// inactive.
+fn parse_slice(input: &str) -> Result<Vec<(usize, usize)>> {
+    let slice = Grid::parse(input, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+    .wrap_err("invalid starting slice")?;
+
+    Ok(slice.positions().filter(|&pos| slice[pos]).collect())
+}
+
+/// One slice of a dimension, one row per line without a trailing newline.
+fn slice(
+    xs: RangeInclusive<isize>,
+    ys: RangeInclusive<isize>,
+    is_active: impl Fn(isize, isize) -> bool,
+) -> String {
+    ys.map(|y| {
+        xs.clone()
+            .map(|x| if is_active(x, y) { '#' } else { '.' })
+            .collect::<String>()
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// The cycles of the boot process in three dimensions for part one and four for part
+/// two, each laid out as its slices side by side in `width` columns.
+pub fn frames(input: &str, part: Part, width: usize) -> Result<Vec<Frame>> {
+    let frame = |cycle: usize, active: usize, slices: Vec<(String, String)>| {
+        Frame::new(
+            format!("cycle {}, {} active", cycle, active),
+            visualize::side_by_side(&slices, width),
+        )
+    };
+
+    let mut frames = Vec::new();
+    match part {
+        Part::One => {
+            let mut dimension = PocketDimension::parse(input)?;
+            for cycle in 0..=6 {
+                if cycle > 0 {
+                    dimension = dimension.step();
+                }
+                frames.push(frame(
+                    cycle,
+                    dimension.active_cubes.len(),
+                    dimension.slices(),
+                ));
+            }
+        }
+        Part::Two => {
+            let mut dimension = HyperDimension::parse(input)?;
+            for cycle in 0..=6 {
+                if cycle > 0 {
+                    dimension = dimension.step();
+                }
+                frames.push(frame(
+                    cycle,
+                    dimension.active_cubes.len(),
+                    dimension.slices(),
+                ));
+            }
+        }
+    }
 
-    Ok(())
+    Ok(frames)
 }
 
+/// The active cubes of the three dimensional pocket dimension.
 #[derive(Default, Clone, PartialEq)]
-struct PocketDimension {
+pub struct PocketDimension {
     active_cubes: BTreeSet<(isize, isize, isize)>,
     min_x: isize,
     max_x: isize,
@@ -62,41 +162,35 @@ struct PocketDimension {
 
 impl Debug for PocketDimension {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for z in self.min_z..=self.max_z {
-            writeln!(f, "\nz={}", z)?;
-            for y in self.min_y..=self.max_y {
-                for x in self.min_x..=self.max_x {
-                    // write!(f, "\nx:{},y:{},z:{},wat:", x, y, z)?;
-                    if self.active_cubes.contains(&(x, y, z)) {
-                        write!(f, "#")?
-                    } else {
-                        write!(f, ".")?
-                    }
-                }
-                writeln!(f)?;
-            }
+        for (label, slice) in self.slices() {
+            writeln!(f, "\n{}\n{}", label, slice)?;
         }
         Ok(())
     }
 }
 
 impl PocketDimension {
-    fn parse(input: &str) -> Result<Self> {
-        let z = 0;
-        let v = input
-            .trim()
-            .lines()
-            .enumerate()
-            .fold(Self::default(), |pd, (y, line)| {
-                line.chars().enumerate().fold(pd, move |mut pd, (x, c)| {
-                    if c == '#' {
-                        pd.insert(x as isize, y as isize, z as isize)
-                    }
-                    pd
-                })
-            });
+    /// Parses the starting slice, `#` is active and `.` inactive.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut pd = Self::default();
+        for (x, y) in parse_slice(input)? {
+            pd.insert(x as isize, y as isize, 0);
+        }
 
-        Ok(v)
+        Ok(pd)
+    }
+
+    /// Each `z` slice, labelled `z=<z>`, with `#` for the active cubes and `.` for the
+    /// inactive ones.
+    pub fn slices(&self) -> Vec<(String, String)> {
+        (self.min_z..=self.max_z)
+            .map(|z| {
+                let slice = slice(self.min_x..=self.max_x, self.min_y..=self.max_y, |x, y| {
+                    self.active_cubes.contains(&(x, y, z))
+                });
+                (format!("z={}", z), slice)
+            })
+            .collect()
     }
 
     fn insert(&mut self, x: isize, y: isize, z: isize) {
@@ -134,7 +228,8 @@ impl PocketDimension {
         }
     }
 
-    fn step(&self) -> Self {
+    /// Runs one cycle.
+    pub fn step(&self) -> Self {
         let mut next = PocketDimension::default();
         for z in self.min_z - 1..=self.max_z + 1 {
             for y in self.min_y - 1..=self.max_y + 1 {
@@ -148,7 +243,8 @@ impl PocketDimension {
         next
     }
 
-    fn boot(self) -> usize {
+    /// Runs the six cycles of the boot process and counts the active cubes.
+    pub fn boot(self) -> usize {
         let mut next = self;
         for _ in 0..6 {
             next = next.step()
@@ -157,8 +253,9 @@ impl PocketDimension {
     }
 }
 
+/// The active cubes of the four dimensional pocket dimension.
 #[derive(Default, Clone, PartialEq)]
-struct HyperDimension {
+pub struct HyperDimension {
     active_cubes: BTreeSet<(isize, isize, isize, isize)>,
     min_x: isize,
     max_x: isize,
@@ -172,45 +269,36 @@ struct HyperDimension {
 
 impl Debug for HyperDimension {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for w in self.min_w..=self.max_w {
-            for z in self.min_z..=self.max_z {
-                writeln!(f, "\nz={}, w={}", z, w)?;
-                for y in self.min_y..=self.max_y {
-                    for x in self.min_x..=self.max_x {
-                        // write!(f, "\nx:{},y:{},z:{},wat:", x, y, z)?;
-                        if self.active_cubes.contains(&(x, y, z, w)) {
-                            write!(f, "#")?
-                        } else {
-                            write!(f, ".")?
-                        }
-                    }
-                    writeln!(f)?;
-                }
-            }
+        for (label, slice) in self.slices() {
+            writeln!(f, "\n{}\n{}", label, slice)?;
         }
-
         Ok(())
     }
 }
 
 impl HyperDimension {
-    fn parse(input: &str) -> Result<Self> {
-        let z = 0;
-        let w = 0;
-        let v = input
-            .trim()
-            .lines()
-            .enumerate()
-            .fold(Self::default(), |pd, (y, line)| {
-                line.chars().enumerate().fold(pd, move |mut pd, (x, c)| {
-                    if c == '#' {
-                        pd.insert(x as isize, y as isize, z as isize, w as isize)
-                    }
-                    pd
-                })
-            });
+    /// Parses the starting slice, `#` is active and `.` inactive.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut pd = Self::default();
+        for (x, y) in parse_slice(input)? {
+            pd.insert(x as isize, y as isize, 0, 0);
+        }
+
+        Ok(pd)
+    }
 
-        Ok(v)
+    /// Each `z`, `w` slice, labelled `z=<z>, w=<w>`, with `#` for the active cubes and
+    /// `.` for the inactive ones.
+    pub fn slices(&self) -> Vec<(String, String)> {
+        (self.min_w..=self.max_w)
+            .flat_map(|w| (self.min_z..=self.max_z).map(move |z| (z, w)))
+            .map(|(z, w)| {
+                let slice = slice(self.min_x..=self.max_x, self.min_y..=self.max_y, |x, y| {
+                    self.active_cubes.contains(&(x, y, z, w))
+                });
+                (format!("z={}, w={}", z, w), slice)
+            })
+            .collect()
     }
 
     fn insert(&mut self, x: isize, y: isize, z: isize, w: isize) {
@@ -252,7 +340,8 @@ impl HyperDimension {
         }
     }
 
-    fn step(&self) -> Self {
+    /// Runs one cycle.
+    pub fn step(&self) -> Self {
         let mut next = Self::default();
         for z in self.min_z - 1..=self.max_z + 1 {
             for w in self.min_w - 1..=self.max_w + 1 {
@@ -268,7 +357,8 @@ impl HyperDimension {
         next
     }
 
-    fn boot(self) -> usize {
+    /// Runs the six cycles of the boot process and counts the active cubes.
+    pub fn boot(self) -> usize {
         let mut next = self;
         for _ in 0..6 {
             next = next.step()
@@ -277,8 +367,9 @@ impl HyperDimension {
     }
 }
 
+/// [`HyperDimension`] with `i8` coordinates in a hash set.
 #[derive(Default, Clone, PartialEq)]
-struct HyperDimensionI8 {
+pub struct HyperDimensionI8 {
     active_cubes: HashSet<(i8, i8, i8, i8)>,
     min_x: i8,
     max_x: i8,
@@ -314,23 +405,14 @@ impl Debug for HyperDimensionI8 {
 }
 
 impl HyperDimensionI8 {
-    fn parse(input: &str) -> Result<Self> {
-        let z = 0;
-        let w = 0;
-        let v = input
-            .trim()
-            .lines()
-            .enumerate()
-            .fold(Self::default(), |pd, (y, line)| {
-                line.chars().enumerate().fold(pd, move |mut pd, (x, c)| {
-                    if c == '#' {
-                        pd.insert(x as i8, y as i8, z as i8, w as i8)
-                    }
-                    pd
-                })
-            });
+    /// Parses the starting slice, `#` is active and `.` inactive.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut pd = Self::default();
+        for (x, y) in parse_slice(input)? {
+            pd.insert(x as i8, y as i8, 0, 0);
+        }
 
-        Ok(v)
+        Ok(pd)
     }
 
     fn insert(&mut self, x: i8, y: i8, z: i8, w: i8) {
@@ -372,7 +454,8 @@ impl HyperDimensionI8 {
         }
     }
 
-    fn step(&self) -> Self {
+    /// Runs one cycle.
+    pub fn step(&self) -> Self {
         let mut next = Self::default();
         for z in self.min_z - 1..=self.max_z + 1 {
             for w in self.min_w - 1..=self.max_w + 1 {
@@ -388,7 +471,8 @@ impl HyperDimensionI8 {
         next
     }
 
-    fn boot(self) -> usize {
+    /// Runs the six cycles of the boot process and counts the active cubes.
+    pub fn boot(self) -> usize {
         let mut next = self;
         for _ in 0..6 {
             next = next.step()
@@ -397,8 +481,9 @@ impl HyperDimensionI8 {
     }
 }
 
+/// [`HyperDimension`] with `i8` array coordinates in a hash set.
 #[derive(Default, Clone, PartialEq)]
-struct HyperDimensionI8Array {
+pub struct HyperDimensionI8Array {
     active_cubes: HashSet<[i8; 4]>,
     min_x: i8,
     max_x: i8,
@@ -434,23 +519,14 @@ impl Debug for HyperDimensionI8Array {
 }
 
 impl HyperDimensionI8Array {
-    fn parse(input: &str) -> Result<Self> {
-        let z = 0;
-        let w = 0;
-        let v = input
-            .trim()
-            .lines()
-            .enumerate()
-            .fold(Self::default(), |pd, (y, line)| {
-                line.chars().enumerate().fold(pd, move |mut pd, (x, c)| {
-                    if c == '#' {
-                        pd.insert(x as i8, y as i8, z as i8, w as i8)
-                    }
-                    pd
-                })
-            });
+    /// Parses the starting slice, `#` is active and `.` inactive.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut pd = Self::default();
+        for (x, y) in parse_slice(input)? {
+            pd.insert(x as i8, y as i8, 0, 0);
+        }
 
-        Ok(v)
+        Ok(pd)
     }
 
     fn insert(&mut self, x: i8, y: i8, z: i8, w: i8) {
@@ -492,7 +568,8 @@ impl HyperDimensionI8Array {
         }
     }
 
-    fn step(&self) -> Self {
+    /// Runs one cycle.
+    pub fn step(&self) -> Self {
         let mut next = Self::default();
         for z in self.min_z - 1..=self.max_z + 1 {
             for w in self.min_w - 1..=self.max_w + 1 {
@@ -508,7 +585,8 @@ impl HyperDimensionI8Array {
         next
     }
 
-    fn boot(self) -> usize {
+    /// Runs the six cycles of the boot process and counts the active cubes.
+    pub fn boot(self) -> usize {
         let mut next = self;
         for _ in 0..6 {
             next = next.step()
@@ -520,6 +598,8 @@ impl HyperDimensionI8Array {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate;
+    use std::collections::HashMap;
 
     #[test]
     fn test_parse() -> Result<()> {
@@ -678,4 +758,72 @@ mod tests {
 
         Ok(())
     }
+
+    /// Boots the slice the slow way, with the cubes as vectors of `dimensions` coordinates.
+    fn boot_reference(input: &str, dimensions: usize) -> Result<usize> {
+        let mut active: HashSet<Vec<isize>> = parse_slice(input)?
+            .into_iter()
+            .map(|(x, y)| {
+                let mut cube = vec![0; dimensions];
+                cube[0] = x as isize;
+                cube[1] = y as isize;
+                cube
+            })
+            .collect();
+
+        let offsets: Vec<Vec<isize>> = (0..3usize.pow(dimensions as u32))
+            .map(|i| {
+                (0..dimensions as u32)
+                    .map(|d| (i / 3usize.pow(d) % 3) as isize - 1)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|offset| offset.iter().any(|&d| d != 0))
+            .collect();
+
+        for _ in 0..6 {
+            let mut neighbours: HashMap<Vec<isize>, usize> = HashMap::new();
+            for cube in &active {
+                for offset in &offsets {
+                    let neighbour = cube.iter().zip(offset).map(|(c, d)| c + d).collect();
+                    *neighbours.entry(neighbour).or_default() += 1;
+                }
+            }
+
+            active = neighbours
+                .into_iter()
+                .filter(|(cube, n)| *n == 3 || (*n == 2 && active.contains(cube)))
+                .map(|(cube, _)| cube)
+                .collect();
+        }
+
+        Ok(active.len())
+    }
+
+    #[test]
+    fn test_dimensions_generated() -> Result<()> {
+        for size in 1..=4 {
+            for seed in 0..3 {
+                let input = generate::generate(17, Some(size), seed)?;
+                let want_3d = boot_reference(&input, 3)?;
+                let want_4d = boot_reference(&input, 4)?;
+
+                assert_eq!(PocketDimension::parse(&input)?.boot(), want_3d, "{}", input);
+                assert_eq!(HyperDimension::parse(&input)?.boot(), want_4d, "{}", input);
+                assert_eq!(
+                    HyperDimensionI8::parse(&input)?.boot(),
+                    want_4d,
+                    "{}",
+                    input
+                );
+                assert_eq!(
+                    HyperDimensionI8Array::parse(&input)?.boot(),
+                    want_4d,
+                    "{}",
+                    input
+                );
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,22 +1,35 @@
+//! Day 18: Operation Order, evaluate the homework with the new precedence rules.
+
+use crate::solution::Solution;
 use eyre::{bail, Result};
 use std::{fmt::Debug, iter::Peekable};
-use tracing::{debug, info, instrument};
+use tracing::{debug, instrument};
+
+/// Sums the expressions evaluated left to right, and then with addition before
+/// multiplication.
+pub struct Day18;
 
-#[instrument]
-pub fn run() -> Result<()> {
-    let input = include_str!("../data/day18.txt");
+impl Solution for Day18 {
+    type Input = Vec<Vec<Token>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-    let sum = calculate(input)?;
-    info!(?sum);
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(tokenize(input))
+    }
 
-    let sum_advanced = calculate_advanced(input)?;
-    info!(?sum_advanced);
+    fn part_1(&self, tokens: &Self::Input) -> Result<Self::Answer1> {
+        calculate(tokens)
+    }
 
-    Ok(())
+    fn part_2(&self, tokens: &Self::Input) -> Result<Self::Answer2> {
+        calculate_advanced(tokens)
+    }
 }
 
+/// A parsed expression.
 #[derive(Clone, PartialEq)]
-enum Expr {
+pub enum Expr {
     Simple(u64),
     Complex {
         lhs: Box<Expr>,
@@ -35,15 +48,16 @@ impl Debug for Expr {
 }
 
 impl Expr {
-    fn evaluate(self) -> u64 {
+    pub fn evaluate(self) -> u64 {
         match self {
             Expr::Simple(n) => n,
             Expr::Complex { lhs, op, rhs } => op.evaluate(*lhs, *rhs),
         }
     }
 
+    /// Parses the tokens up to the end or a closing paren, evaluating left to right.
     #[instrument(skip(iter))]
-    fn parse<I: Iterator<Item = Token>>(iter: &mut Peekable<I>) -> Result<Self> {
+    pub fn parse<I: Iterator<Item = Token>>(iter: &mut Peekable<I>) -> Result<Self> {
         let mut expr = match iter.next() {
             Some(t) => match t {
                 Token::Operator(_) => bail!("unexpected operator"),
@@ -79,8 +93,9 @@ impl Expr {
         }
     }
 
+    /// Like [`Expr::parse`], but addition is evaluated before multiplication.
     #[instrument(skip(iter))]
-    fn parse_advanced<I: Iterator<Item = Token>>(iter: &mut Peekable<I>) -> Result<Self> {
+    pub fn parse_advanced<I: Iterator<Item = Token>>(iter: &mut Peekable<I>) -> Result<Self> {
         let mut lhs = match iter.next() {
             Some(t) => match t {
                 Token::Operator(_) => bail!("unexpected operator"),
@@ -130,7 +145,7 @@ impl Expr {
 }
 
 #[derive(Clone, Copy, PartialEq)]
-enum Operator {
+pub enum Operator {
     Add,
     Mul,
 }
@@ -145,7 +160,7 @@ impl Debug for Operator {
 }
 
 impl Operator {
-    fn evaluate(&self, lhs: Expr, rhs: Expr) -> u64 {
+    pub fn evaluate(&self, lhs: Expr, rhs: Expr) -> u64 {
         match self {
             Operator::Add => lhs.evaluate() + rhs.evaluate(),
             Operator::Mul => lhs.evaluate() * rhs.evaluate(),
@@ -154,14 +169,15 @@ impl Operator {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum Token {
+pub enum Token {
     Operator(Operator),
     Num(u64),
     OpenParen,
     CloseParen,
 }
 
-fn tokenize(input: &str) -> Vec<Vec<Token>> {
+/// Splits each line into tokens, the numbers are single digits.
+pub fn tokenize(input: &str) -> Vec<Vec<Token>> {
     input
         .trim()
         .lines()
@@ -175,7 +191,7 @@ fn tokenize(input: &str) -> Vec<Vec<Token>> {
                     '*' => Some(Token::Operator(Operator::Mul)),
                     _ => {
                         let n = c.to_digit(10).unwrap() as u64;
-                        if n < 1 || n > 10 {
+                        if !(1..=10).contains(&n) {
                             panic!("invalid n ! : {}", n);
                         }
                         Some(Token::Num(n))
@@ -186,34 +202,32 @@ fn tokenize(input: &str) -> Vec<Vec<Token>> {
         .collect()
 }
 
-fn calculate(input: &str) -> Result<u64> {
-    let exprs = tokenize(input)
-        .into_iter()
-        .map(|tokens| Ok(Expr::parse(&mut tokens.into_iter().peekable())?))
+/// Sums the expressions, evaluated left to right.
+pub fn calculate(tokens: &[Vec<Token>]) -> Result<u64> {
+    let exprs = tokens
+        .iter()
+        .map(|tokens| Expr::parse(&mut tokens.iter().cloned().peekable()))
         .collect::<Result<Vec<_>>>()?;
 
     Ok(exprs
         .into_iter()
-        .zip(input.lines())
-        .map(|(expr, line)| {
-            debug!(?line);
+        .map(|expr| {
             debug!(?expr);
             expr.evaluate()
         })
         .sum())
 }
 
-fn calculate_advanced(input: &str) -> Result<u64> {
-    let exprs = tokenize(input)
-        .into_iter()
-        .map(|tokens| Ok(Expr::parse_advanced(&mut tokens.into_iter().peekable())?))
+/// Sums the expressions, with addition evaluated before multiplication.
+pub fn calculate_advanced(tokens: &[Vec<Token>]) -> Result<u64> {
+    let exprs = tokens
+        .iter()
+        .map(|tokens| Expr::parse_advanced(&mut tokens.iter().cloned().peekable()))
         .collect::<Result<Vec<_>>>()?;
 
     Ok(exprs
         .into_iter()
-        .zip(input.lines())
-        .map(|(expr, line)| {
-            debug!(?line);
+        .map(|expr| {
             debug!(?expr);
             expr.evaluate()
         })
@@ -236,14 +250,14 @@ mod tests {
         let want = vec![71, 51, 26, 437, 12240, 13632];
         let want_sum: u64 = want.iter().sum();
 
-        for ((expr, want), input) in exprs.into_iter().zip(want.into_iter()).zip(input.lines()) {
+        for ((expr, want), input) in exprs.into_iter().zip(want).zip(input.lines()) {
             debug!(?input);
             debug!(?expr);
             let got = expr.evaluate();
             assert_eq!(got, want);
         }
 
-        let got_sum = calculate(input)?;
+        let got_sum = calculate(&tokenize(input))?;
         assert_eq!(want_sum, got_sum);
 
         Ok(())
@@ -261,14 +275,14 @@ mod tests {
         let want = vec![231, 51, 46, 1445, 669060, 23340];
         let want_sum: u64 = want.iter().sum();
 
-        for ((expr, want), input) in exprs.into_iter().zip(want.into_iter()).zip(input.lines()) {
+        for ((expr, want), input) in exprs.into_iter().zip(want).zip(input.lines()) {
             debug!(?input);
             debug!(?expr);
             let got = expr.evaluate();
             assert_eq!(got, want);
         }
 
-        let got_sum = calculate_advanced(input)?;
+        let got_sum = calculate_advanced(&tokenize(input))?;
         assert_eq!(want_sum, got_sum);
 
         Ok(())
@@ -1,114 +1,97 @@
-use eyre::{eyre, Context, Result};
-use std::{cell::RefCell, collections::HashMap, fmt::Debug, str::FromStr, time::Instant};
-use tracing::{info, instrument, trace};
+//! Day 19: Monster Messages, match the messages against the grammar of rules.
+
+use crate::{
+    parse::{self, ParseError, Span, WithSnippet},
+    solution::Solution,
+};
+use eyre::{eyre, Result};
+use std::{cell::RefCell, collections::HashMap, fmt::Debug};
+use tracing::{instrument, trace};
+
+/// Counts the messages that match rule 0, and then the ones that match once rules 8 and
+/// 11 loop.
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (RuleMatcher, Vec<String>);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let (matcher, msgs) = RuleMatcher::parse(input)?;
+        let msgs = msgs.into_iter().map(String::from).collect();
+
+        Ok((matcher, msgs))
+    }
 
-#[instrument]
-pub fn run() -> Result<()> {
-    let (mut matcher, msgs) = RuleMatcher::from_str(include_str!("../data/day19.txt"))?;
+    fn part_1(&self, (matcher, msgs): &Self::Input) -> Result<Self::Answer1> {
+        let msgs: Vec<&str> = msgs.iter().map(String::as_str).collect();
 
-    let valid_messages = matcher.test_msgs(&msgs)?;
-    info!(valid_messages);
+        matcher.test_msgs(&msgs)
+    }
 
-    matcher.enable_part_2();
+    fn part_2(&self, (matcher, msgs): &Self::Input) -> Result<Self::Answer2> {
+        let msgs: Vec<&str> = msgs.iter().map(String::as_str).collect();
 
-    let valid_messages_part_2 = matcher.test_msgs(&msgs)?;
-    info!(valid_messages_part_2);
+        let mut matcher = matcher.clone();
+        matcher.enable_part_2();
 
-    Ok(())
+        matcher.test_msgs(&msgs)
+    }
 }
 
+/// A rule, the ids refer to other rules.
 #[derive(Debug, Clone)]
-enum Rule {
+pub enum Rule {
     Char(char),
     Other(Vec<usize>),
     Either(Vec<usize>, Vec<usize>),
 }
 
-trait Day19Str {
-    fn parse_rule_id(&'static self) -> Result<usize>;
-    fn parse_rule_list(&'static self) -> Result<Vec<usize>>;
-    fn parse_rule(&'static self) -> Result<(usize, Rule)>;
-    fn parse_input(&'static self) -> Result<(HashMap<usize, Rule>, Vec<&'static str>)>;
-}
+fn parse_rule(line: Span) -> Result<(usize, Rule), ParseError> {
+    let (id, rule) = line.split_once(": ")?;
+    let id = id.parse()?;
 
-impl Day19Str for str {
-    fn parse_rule_id(&'static self) -> Result<usize> {
-        self.parse().context(self)
+    if rule.as_str().starts_with('"') {
+        let c = rule.strip_prefix("\"")?.strip_suffix("\"")?.parse()?;
+        return Ok((id, Rule::Char(c)));
     }
 
-    fn parse_rule_list(&'static self) -> Result<Vec<usize>> {
-        self.split(' ')
-            .map(|s| s.parse().map_err(eyre::Report::new).context(s))
-            .collect()
+    match rule.split_once(" | ") {
+        Ok((rule_ids, other_rule_ids)) => Ok((
+            id,
+            Rule::Either(rule_ids.parse_list(" ")?, other_rule_ids.parse_list(" ")?),
+        )),
+        Err(_) => Ok((id, Rule::Other(rule.parse_list(" ")?))),
     }
+}
 
-    fn parse_rule(&'static self) -> Result<(usize, Rule)> {
-        let mut iter = self.split(": ");
-        let id_str = iter
-            .next()
-            .ok_or_else(|| eyre!("unexpected end of input"))
-            .context(self)?;
-
-        let id = id_str.parse_rule_id()?;
+fn parse_input(input: Span<'_>) -> Result<(HashMap<usize, Rule>, Vec<&str>), ParseError> {
+    let mut sections = input.records();
 
-        let rule_str = iter
-            .next()
-            .ok_or_else(|| eyre!("unexpected end of input"))
-            .context(self)?;
+    let rules = parse::next(&mut sections, &input, "the rules")?
+        .lines()
+        .map(parse_rule)
+        .collect::<Result<_, _>>()?;
 
-        if rule_str.starts_with('"') {
-            return Ok((id, Rule::Char(rule_str.chars().nth(1).unwrap())));
-        }
+    let messages = parse::next(&mut sections, &input, "the messages")?
+        .lines()
+        .map(|line| line.as_str())
+        .collect();
 
-        let mut iter = rule_str.split(" | ");
-
-        let rule_ids = iter
-            .next()
-            .ok_or_else(|| eyre!("unexpected end of input"))
-            .context(self)?
-            .parse_rule_list()
-            .unwrap();
-
-        if let Some(other_rule_ids) = iter.next() {
-            let other_rule_ids = other_rule_ids
-                .split(' ')
-                .map(usize::from_str)
-                .collect::<Result<Vec<_>, _>>()
-                .unwrap();
-            Ok((id, Rule::Either(rule_ids, other_rule_ids)))
-        } else {
-            Ok((id, Rule::Other(rule_ids)))
-        }
-    }
-
-    fn parse_input(&'static self) -> Result<(HashMap<usize, Rule>, Vec<&'static str>)> {
-        let mut iter = self.split("\n\n");
-        let rules = iter
-            .next()
-            .ok_or_else(|| eyre!("unexpected end of input"))
-            .context(self)?
-            .lines()
-            .map(str::parse_rule)
-            .collect::<Result<_>>()?;
-
-        let messages = iter
-            .next()
-            .ok_or_else(|| eyre!("unexpected end of input"))
-            .context(self)?
-            .lines()
-            .collect();
-
-        Ok((rules, messages))
-    }
+    Ok((rules, messages))
 }
 
-struct RuleMatcher {
+/// Matches messages against rule 0 of a set of rules.
+#[derive(Clone)]
+pub struct RuleMatcher {
     rules: HashMap<usize, Rule>,
     max_recurse: RefCell<HashMap<usize, usize>>,
     current_recurse: RefCell<HashMap<usize, usize>>,
     recurse_limit: usize,
 }
 
+/// How far a message matched.
 #[derive(Debug, Clone)]
 pub enum Match {
     Finished,
@@ -116,7 +99,7 @@ pub enum Match {
 }
 
 impl RuleMatcher {
-    fn new(rules: HashMap<usize, Rule>) -> Self {
+    pub fn new(rules: HashMap<usize, Rule>) -> Self {
         Self {
             rules,
             max_recurse: Default::default(),
@@ -131,7 +114,7 @@ impl RuleMatcher {
     }
 
     #[instrument(level = "trace", name = "m", skip(self, msg))]
-    fn matches_rule(&self, msg: &'static str, i: usize, id: usize) -> Result<Option<Match>> {
+    fn matches_rule(&self, msg: &str, i: usize, id: usize) -> Result<Option<Match>> {
         let max = self.max_recurse.borrow().get(&id).cloned();
         if let Some(max) = max {
             let current = self
@@ -183,15 +166,10 @@ impl RuleMatcher {
                 let b_list = &rule_list_b[shared..];
 
                 match self.matches_rule_list(msg, i, shared_list)? {
-                    Some(Match::Next(i)) => {
-                        if let Some(n) = self.matches_rule_list(msg, i, a_list)? {
-                            Some(n)
-                        } else if let Some(n) = self.matches_rule_list(msg, i, b_list)? {
-                            Some(n)
-                        } else {
-                            None
-                        }
-                    }
+                    Some(Match::Next(i)) => match self.matches_rule_list(msg, i, a_list)? {
+                        Some(n) => Some(n),
+                        None => self.matches_rule_list(msg, i, b_list)?,
+                    },
                     _ => None,
                 }
             }
@@ -207,12 +185,7 @@ impl RuleMatcher {
     }
 
     #[instrument(level = "trace", name = "l", skip(self, msg, ids))]
-    fn matches_rule_list(
-        &self,
-        msg: &'static str,
-        mut i: usize,
-        ids: &[usize],
-    ) -> Result<Option<Match>> {
+    fn matches_rule_list(&self, msg: &str, mut i: usize, ids: &[usize]) -> Result<Option<Match>> {
         let mut iter = ids.iter().peekable();
         while let Some(id) = iter.next() {
             let res = self.matches_rule(msg, i, *id)?;
@@ -244,7 +217,8 @@ impl RuleMatcher {
     }
 
     #[instrument(level = "trace", name = "t" skip(self), fields(len = msg.len()))]
-    fn test_msg(&self, msg: &'static str) -> Result<bool> {
+    /// Whether all of `msg` matches rule 0.
+    pub fn test_msg(&self, msg: &str) -> Result<bool> {
         self.clear();
 
         for i in 0..self.recurse_limit {
@@ -270,9 +244,9 @@ impl RuleMatcher {
         Ok(false)
     }
 
-    fn test_msgs(&self, msgs: &[&'static str]) -> Result<usize> {
+    /// Counts the messages that match rule 0.
+    pub fn test_msgs(&self, msgs: &[&str]) -> Result<usize> {
         let mut valid = 0;
-        let now = Instant::now();
 
         for &msg in msgs {
             if self.test_msg(msg)? {
@@ -280,21 +254,20 @@ impl RuleMatcher {
             }
         }
 
-        let elapsed_ms = now.elapsed().as_millis();
-        info!(?elapsed_ms);
-
         Ok(valid)
     }
 
-    fn enable_part_2(&mut self) {
+    /// Replaces rules 8 and 11 with the looping versions from part 2.
+    pub fn enable_part_2(&mut self) {
         // these rules are changed from the instructions to match how the matcher works
         self.rules.insert(8, Rule::Other(vec![42, 8]));
         self.rules.insert(11, Rule::Other(vec![42, 11, 31]));
         self.recurse_limit = 5;
     }
 
-    fn from_str(input: &'static str) -> Result<(Self, Vec<&'static str>)> {
-        let (rules, msgs) = input.parse_input()?;
+    /// Parses the rules and the messages, separated by a blank line.
+    pub fn parse(input: &str) -> Result<(Self, Vec<&str>)> {
+        let (rules, msgs) = parse_input(Span::new(input)).with_snippet()?;
 
         trace!(?rules);
         trace!(?msgs);
@@ -310,7 +283,7 @@ mod tests {
 
     #[test]
     fn test_parse() -> Result<()> {
-        let (matcher, msgs) = RuleMatcher::from_str(include_str!("../data/day19_test.txt"))?;
+        let (matcher, msgs) = RuleMatcher::parse(include_str!("../data/day19_test.txt"))?;
 
         let result = msgs
             .iter()
@@ -324,9 +297,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_invalid() {
+        let tests = vec![
+            (
+                "0: 1 2\n1: \"ab\"\n\nab",
+                "invalid input on line 2, column 5: invalid value: 'ab', too many characters in string",
+            ),
+            (
+                "0: 1 | x\n\nab",
+                "invalid input on line 1, column 8: invalid value: 'x', invalid digit found in string",
+            ),
+            (
+                "0: 1 2\n1: \"a\"\n",
+                "invalid input on line 3, column 1: unexpected end of input, expected the messages",
+            ),
+        ];
+
+        for (input, want) in tests {
+            let err = RuleMatcher::parse(input).map(|_| ()).unwrap_err();
+            assert_eq!(format!("{:#}", err), want);
+        }
+    }
+
     #[test]
     fn test_msgs() -> Result<()> {
-        let (matcher, msgs) = RuleMatcher::from_str(include_str!("../data/day19_test.txt"))?;
+        let (matcher, msgs) = RuleMatcher::parse(include_str!("../data/day19_test.txt"))?;
 
         let result = matcher.test_msgs(&msgs)?;
 
@@ -337,40 +333,25 @@ mod tests {
 
     #[test]
     fn test_msgs_2() -> Result<()> {
-        let (mut matcher, _msgs) = RuleMatcher::from_str(include_str!("../data/day19_test_2.txt"))?;
+        let (mut matcher, _msgs) = RuleMatcher::parse(include_str!("../data/day19_test_2.txt"))?;
 
         matcher.enable_part_2();
 
-        assert_eq!(
-            matcher.test_msg("abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa")?,
-            false
-        );
-        assert_eq!(matcher.test_msg("bbabbbbaabaabba")?, true);
-        assert_eq!(matcher.test_msg("babbbbaabbbbbabbbbbbaabaaabaaa")?, true);
-        assert_eq!(
-            matcher.test_msg("aaabbbbbbaaaabaababaabababbabaaabbababababaaa")?,
-            true
-        );
-        assert_eq!(matcher.test_msg("bbbbbbbaaaabbbbaaabbabaaa")?, true);
-        assert_eq!(
-            matcher.test_msg("bbbababbbbaaaaaaaabbababaaababaabab")?,
-            true
-        );
-        assert_eq!(matcher.test_msg("ababaaaaaabaaab")?, true);
-        assert_eq!(matcher.test_msg("ababaaaaabbbaba")?, true);
-        assert_eq!(matcher.test_msg("baabbaaaabbaaaababbaababb")?, true);
-        assert_eq!(matcher.test_msg("abbbbabbbbaaaababbbbbbaaaababb")?, true);
-        assert_eq!(matcher.test_msg("aaaaabbaabaaaaababaa")?, true);
-        assert_eq!(matcher.test_msg("aaaabbaaaabbaaa")?, false);
-        assert_eq!(
-            matcher.test_msg("aaaabbaabbaaaaaaabbbabbbaaabbaabaaa")?,
-            true
-        );
-        assert_eq!(matcher.test_msg("babaaabbbaaabaababbaabababaaab")?, false);
-        assert_eq!(
-            matcher.test_msg("aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba")?,
-            true
-        );
+        assert!(!matcher.test_msg("abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa")?);
+        assert!(matcher.test_msg("bbabbbbaabaabba")?);
+        assert!(matcher.test_msg("babbbbaabbbbbabbbbbbaabaaabaaa")?);
+        assert!(matcher.test_msg("aaabbbbbbaaaabaababaabababbabaaabbababababaaa")?);
+        assert!(matcher.test_msg("bbbbbbbaaaabbbbaaabbabaaa")?);
+        assert!(matcher.test_msg("bbbababbbbaaaaaaaabbababaaababaabab")?);
+        assert!(matcher.test_msg("ababaaaaaabaaab")?);
+        assert!(matcher.test_msg("ababaaaaabbbaba")?);
+        assert!(matcher.test_msg("baabbaaaabbaaaababbaababb")?);
+        assert!(matcher.test_msg("abbbbabbbbaaaababbbbbbaaaababb")?);
+        assert!(matcher.test_msg("aaaaabbaabaaaaababaa")?);
+        assert!(!matcher.test_msg("aaaabbaaaabbaaa")?);
+        assert!(matcher.test_msg("aaaabbaabbaaaaaaabbbabbbaaabbaabaaa")?);
+        assert!(!matcher.test_msg("babaaabbbaaabaababbaabababaaab")?);
+        assert!(matcher.test_msg("aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba")?);
 
         Ok(())
     }
@@ -378,18 +359,18 @@ mod tests {
     #[test]
     fn test_msgs_custom() -> Result<()> {
         let (mut matcher, _msgs) =
-            RuleMatcher::from_str(include_str!("../data/day19_test_custom.txt"))?;
+            RuleMatcher::parse(include_str!("../data/day19_test_custom.txt"))?;
 
         matcher.enable_part_2();
 
-        assert_eq!(matcher.test_msg("a")?, false);
-        assert_eq!(matcher.test_msg("ab")?, false);
-        assert_eq!(matcher.test_msg("b")?, false);
+        assert!(!matcher.test_msg("a")?);
+        assert!(!matcher.test_msg("ab")?);
+        assert!(!matcher.test_msg("b")?);
 
-        assert_eq!(matcher.test_msg("aaaabb")?, true, "2 x 8, 2 x 42");
-        assert_eq!(matcher.test_msg("aaabb")?, true, "1 x 8, 2 x 42");
-        assert_eq!(matcher.test_msg("aaab")?, true, "2 x 8, 1 x 42");
-        assert_eq!(matcher.test_msg("aab")?, true, "1 x 8, 1 x 42");
+        assert!(matcher.test_msg("aaaabb")?, "2 x 8, 2 x 42");
+        assert!(matcher.test_msg("aaabb")?, "1 x 8, 2 x 42");
+        assert!(matcher.test_msg("aaab")?, "2 x 8, 1 x 42");
+        assert!(matcher.test_msg("aab")?, "1 x 8, 1 x 42");
 
         Ok(())
     }
@@ -1,40 +1,471 @@
-pub fn run() {
-    let input = include_str!("../data/day2.txt")
-        .trim()
-        .split_terminator('\n')
-        .map(|input| {
-            let mut input = input.splitn(2, '-');
-            let min = input.next().unwrap().parse::<usize>().unwrap();
+//! Day 2: Password Philosophy, count the passwords that follow their policy.
 
-            let mut input = input.next().unwrap().splitn(2, ' ');
-            let max = input.next().unwrap().parse::<usize>().unwrap();
+use crate::{parse, solution::Solution};
+use eyre::{Result, WrapErr};
+use regex::Regex;
+use std::{collections::HashSet, fmt, num::ParseIntError, str::FromStr};
+use thiserror::Error;
 
-            let mut input = input.next().unwrap().chars();
-            let letter = input.next().unwrap();
+/// Counts the valid passwords with the count policy, and then the position policy.
+pub struct Day2;
 
-            let password: Vec<char> = input.skip(2).collect();
+impl Solution for Day2 {
+    type Input = Vec<PasswordEntry>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-            (min, max, letter, password)
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(count_valid(input, &CountPolicy))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(count_valid(input, &PositionPolicy))
+    }
+}
+
+/// A line of the password database, `<min>-<max> <letter>: <password>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordEntry {
+    /// The line of the entry in the input, counting from 1, a single parsed line is line 1
+    pub line: usize,
+    pub min: usize,
+    pub max: usize,
+    pub letter: char,
+    pub password: String,
+}
+
+#[derive(Error, Debug)]
+pub enum PasswordParseError {
+    #[error("invalid line: '{0}', expected '<min>-<max> <letter>: <password>'")]
+    InvalidLine(String),
+    #[error("invalid count: '{0}'")]
+    InvalidCount(String, #[source] ParseIntError),
+    #[error("invalid range: '{0}-{1}', the minimum is larger than the maximum")]
+    InvalidRange(usize, usize),
+}
+
+fn parse_count(s: &str) -> Result<usize, PasswordParseError> {
+    usize::from_str(s).map_err(|e| PasswordParseError::InvalidCount(s.to_string(), e))
+}
+
+impl FromStr for PasswordEntry {
+    type Err = PasswordParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid_line = || PasswordParseError::InvalidLine(line.to_string());
+
+        let (policy, password) = line.split_once(": ").ok_or_else(invalid_line)?;
+        let (counts, letter) = policy.split_once(' ').ok_or_else(invalid_line)?;
+        let (min, max) = counts.split_once('-').ok_or_else(invalid_line)?;
+
+        let mut chars = letter.chars();
+        let letter = match (chars.next(), chars.next()) {
+            (Some(letter), None) => letter,
+            _ => return Err(invalid_line()),
+        };
+
+        let (min, max) = (parse_count(min)?, parse_count(max)?);
+        if min > max {
+            return Err(PasswordParseError::InvalidRange(min, max));
+        }
+
+        Ok(Self {
+            line: 1,
+            min,
+            max,
+            letter,
+            password: password.to_string(),
         })
-        .collect::<Vec<_>>();
+    }
+}
 
-    let a = input
-        .iter()
-        .filter(|(min, max, letter, password)| {
-            let count = password.iter().filter(|c| *c == letter).count();
-            count >= *min && count <= *max
+impl fmt::Display for PasswordEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} {}: {}",
+            self.min, self.max, self.letter, self.password
+        )
+    }
+}
+
+/// Parses one password per line.
+pub fn parse_input(input: &str) -> Result<Vec<PasswordEntry>> {
+    parse::numbered_lines(input)
+        .map(|(number, line)| {
+            line.parse()
+                .map(|entry| PasswordEntry {
+                    line: number,
+                    ..entry
+                })
+                .wrap_err_with(|| format!("invalid input on line {}", number))
         })
-        .count();
+        .collect()
+}
+
+/// A rule a password has to follow.
+pub trait PasswordPolicy: fmt::Debug + Send + Sync {
+    /// The name that selects the policy in [`parse_policy`].
+    fn name(&self) -> String;
+
+    /// Why the password does not follow the policy, if it does not.
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String>;
+
+    fn is_valid(&self, entry: &PasswordEntry) -> bool {
+        self.check(entry).is_ok()
+    }
+}
+
+/// The letter appears between min and max times, part 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountPolicy;
+
+impl PasswordPolicy for CountPolicy {
+    fn name(&self) -> String {
+        "count".to_string()
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let count = entry
+            .password
+            .chars()
+            .filter(|&c| c == entry.letter)
+            .count();
+        if count >= entry.min && count <= entry.max {
+            return Ok(());
+        }
+
+        Err(format!(
+            "letter '{}' appears {} {}, allowed {}-{}",
+            entry.letter,
+            count,
+            if count == 1 { "time" } else { "times" },
+            entry.min,
+            entry.max
+        ))
+    }
+}
+
+/// The letter is at exactly one of the positions min and max, counting from 1, part 2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionPolicy;
+
+impl PasswordPolicy for PositionPolicy {
+    fn name(&self) -> String {
+        "position".to_string()
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let at = |position: usize| {
+            position
+                .checked_sub(1)
+                .and_then(|i| entry.password.chars().nth(i))
+                == Some(entry.letter)
+        };
+
+        match (at(entry.min), at(entry.max)) {
+            (true, true) => Err(format!(
+                "letter '{}' is at both positions {} and {}, expected exactly one",
+                entry.letter, entry.min, entry.max
+            )),
+            (false, false) => Err(format!(
+                "letter '{}' is at neither position {} nor {}, expected exactly one",
+                entry.letter, entry.min, entry.max
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The password matches a regular expression, anchor it with `^` and `$` to match the
+/// whole password.
+#[derive(Debug, Clone)]
+pub struct PatternPolicy(pub Regex);
+
+impl PasswordPolicy for PatternPolicy {
+    fn name(&self) -> String {
+        format!("pattern={}", self.0)
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        if self.0.is_match(&entry.password) {
+            Ok(())
+        } else {
+            Err(format!("does not match '{}'", self.0))
+        }
+    }
+}
+
+/// None of the characters appear in the password.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForbiddenPolicy(pub String);
 
-    let b = input
+impl PasswordPolicy for ForbiddenPolicy {
+    fn name(&self) -> String {
+        format!("forbid={}", self.0)
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        match entry.password.chars().find(|&c| self.0.contains(c)) {
+            Some(c) => Err(format!("contains the forbidden character '{}'", c)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The password has at least this many different characters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistinctPolicy(pub usize);
+
+impl PasswordPolicy for DistinctPolicy {
+    fn name(&self) -> String {
+        format!("distinct={}", self.0)
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let distinct = entry.password.chars().collect::<HashSet<_>>().len();
+        if distinct >= self.0 {
+            Ok(())
+        } else {
+            Err(format!(
+                "has {} different characters, expected at least {}",
+                distinct, self.0
+            ))
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PolicyParseError {
+    #[error(
+        "invalid policy: '{0}', expected count, position, pattern=<regex>, forbid=<chars> \
+         or distinct=<n>"
+    )]
+    InvalidPolicy(String),
+    #[error("invalid pattern: '{0}'")]
+    InvalidPattern(String, #[source] regex::Error),
+    #[error("invalid number of distinct characters: '{0}'")]
+    InvalidDistinct(String, #[source] ParseIntError),
+}
+
+/// The policy named `count`, `position`, `pattern=<regex>`, `forbid=<chars>` or
+/// `distinct=<n>`.
+pub fn parse_policy(s: &str) -> Result<Box<dyn PasswordPolicy>, PolicyParseError> {
+    let (name, argument) = match s.split_once('=') {
+        Some((name, argument)) => (name, Some(argument)),
+        None => (s, None),
+    };
+
+    match (name.trim(), argument) {
+        ("count", None) => Ok(Box::new(CountPolicy)),
+        ("position", None) => Ok(Box::new(PositionPolicy)),
+        ("pattern", Some(pattern)) => Regex::new(pattern)
+            .map(|regex| Box::new(PatternPolicy(regex)) as Box<dyn PasswordPolicy>)
+            .map_err(|e| PolicyParseError::InvalidPattern(pattern.to_string(), e)),
+        ("forbid", Some(chars)) if !chars.is_empty() => {
+            Ok(Box::new(ForbiddenPolicy(chars.to_string())))
+        }
+        ("distinct", Some(n)) => usize::from_str(n)
+            .map(|n| Box::new(DistinctPolicy(n)) as Box<dyn PasswordPolicy>)
+            .map_err(|e| PolicyParseError::InvalidDistinct(n.to_string(), e)),
+        _ => Err(PolicyParseError::InvalidPolicy(s.to_string())),
+    }
+}
+
+/// How many of the passwords follow the policy.
+pub fn count_valid(entries: &[PasswordEntry], policy: &dyn PasswordPolicy) -> usize {
+    entries
         .iter()
-        .filter(|(i, j, letter, password)| {
-            let a = password.get(*i - 1);
-            let b = password.get(*j - 1);
+        .filter(|entry| policy.is_valid(entry))
+        .count()
+}
 
-            a != b && (a == Some(letter) || b == Some(letter))
-        })
-        .count();
+/// `<policy>: valid`, or `<policy>: invalid, <reason>`.
+fn format_verdict(policy: &dyn PasswordPolicy, entry: &PasswordEntry) -> String {
+    match policy.check(entry) {
+        Ok(()) => format!("{}: valid", policy.name()),
+        Err(reason) => format!("{}: invalid, {}", policy.name(), reason),
+    }
+}
+
+/// Every entry with its line and the verdict of each policy below it:
+///
+/// ```text
+/// line 2: 1-3 b: cdefg
+///   count: invalid, letter 'b' appears 0 times, allowed 1-3
+///   position: invalid, letter 'b' is at neither position 1 nor 3, expected exactly one
+/// ```
+pub fn report(entries: &[PasswordEntry], policies: &[Box<dyn PasswordPolicy>]) -> String {
+    let mut lines = Vec::new();
+    for entry in entries {
+        lines.push(format!("line {}: {}", entry.line, entry));
+        for policy in policies {
+            lines.push(format!("  {}", format_verdict(policy.as_ref(), entry)));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// The entries that one of the policies accepts and the other rejects, in the format of
+/// [`report`].
+pub fn diff(entries: &[PasswordEntry], a: &dyn PasswordPolicy, b: &dyn PasswordPolicy) -> String {
+    let mut lines = Vec::new();
+    for entry in entries {
+        if a.is_valid(entry) != b.is_valid(entry) {
+            lines.push(format!("line {}: {}", entry.line, entry));
+            lines.push(format!("  {}", format_verdict(a, entry)));
+            lines.push(format!("  {}", format_verdict(b, entry)));
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line: usize, min: usize, max: usize, letter: char, password: &str) -> PasswordEntry {
+        PasswordEntry {
+            line,
+            min,
+            max,
+            letter,
+            password: password.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_input() -> Result<()> {
+        let input = parse_input("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n")?;
+
+        assert_eq!(
+            input,
+            vec![
+                entry(1, 1, 3, 'a', "abcde"),
+                entry(2, 1, 3, 'b', "cdefg"),
+                entry(3, 2, 9, 'c', "ccccccccc"),
+            ]
+        );
+
+        assert_eq!(Day2.part_1(&input)?, 2);
+        assert_eq!(Day2.part_2(&input)?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let tests = vec![
+            (
+                "1-3 a: abcde\n1-x b: cdefg",
+                "invalid input on line 2: invalid count: 'x': invalid digit found in string",
+            ),
+            (
+                "1-3 a abcde",
+                "invalid input on line 1: invalid line: '1-3 a abcde', expected '<min>-<max> <letter>: <password>'",
+            ),
+            (
+                "1-3 ab: abcde",
+                "invalid input on line 1: invalid line: '1-3 ab: abcde', expected '<min>-<max> <letter>: <password>'",
+            ),
+            (
+                "\n1-3 a: abcde\n1-x b: cdefg\n\n",
+                "invalid input on line 3: invalid count: 'x': invalid digit found in string",
+            ),
+            (
+                "3-1 a: abcde",
+                "invalid input on line 1: invalid range: '3-1', the minimum is larger than the maximum",
+            ),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(format!("{:#}", parse_input(input).unwrap_err()), want);
+        }
+    }
+
+    #[test]
+    fn test_policies() -> Result<()> {
+        let entries = parse_input("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n")?;
+        let tests = vec![
+            ("count", vec![true, false, true]),
+            ("position", vec![true, false, false]),
+            ("pattern=^[a-e]+$", vec![true, false, true]),
+            ("forbid=ab", vec![false, true, true]),
+            ("distinct=5", vec![true, true, false]),
+            ("distinct=0", vec![true, true, true]),
+        ];
+
+        for (name, want) in tests {
+            let policy = parse_policy(name)?;
+            assert_eq!(policy.name(), name);
+
+            let valid: Vec<bool> = entries.iter().map(|e| policy.is_valid(e)).collect();
+            assert_eq!(valid, want, "{}", name);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_report() -> Result<()> {
+        let entries = parse_input("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n")?;
+        let policies = vec![parse_policy("count")?, parse_policy("distinct=5")?];
+
+        assert_eq!(
+            report(&entries, &policies),
+            "line 1: 1-3 a: abcde\n\
+             \x20 count: valid\n\
+             \x20 distinct=5: valid\n\
+             line 2: 1-3 b: cdefg\n\
+             \x20 count: invalid, letter 'b' appears 0 times, allowed 1-3\n\
+             \x20 distinct=5: valid\n\
+             line 3: 2-9 c: ccccccccc\n\
+             \x20 count: valid\n\
+             \x20 distinct=5: invalid, has 1 different characters, expected at least 5"
+        );
+
+        assert_eq!(
+            diff(&entries, &CountPolicy, &PositionPolicy),
+            "line 3: 2-9 c: ccccccccc\n\
+             \x20 count: valid\n\
+             \x20 position: invalid, letter 'c' is at both positions 2 and 9, expected exactly one"
+        );
+
+        let entries = parse_input("\n\n1-3 a: abcde\n1-3 b: cdefg\n")?;
+        assert_eq!(
+            report(&entries, &[parse_policy("count")?]),
+            "line 3: 1-3 a: abcde\n\
+             \x20 count: valid\n\
+             line 4: 1-3 b: cdefg\n\
+             \x20 count: invalid, letter 'b' appears 0 times, allowed 1-3",
+            "the lines are the ones in the input, leading blank lines are counted"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_policy_invalid() {
+        let tests = vec![
+            ("size", "invalid policy: 'size', expected count, position, pattern=<regex>, forbid=<chars> or distinct=<n>"),
+            ("count=3", "invalid policy: 'count=3', expected count, position, pattern=<regex>, forbid=<chars> or distinct=<n>"),
+            ("forbid=", "invalid policy: 'forbid=', expected count, position, pattern=<regex>, forbid=<chars> or distinct=<n>"),
+            ("distinct=x", "invalid number of distinct characters: 'x': invalid digit found in string"),
+        ];
 
-    println!("day 2: {}, {}", a, b);
+        for (input, want) in tests {
+            let e = eyre::Report::new(parse_policy(input).unwrap_err());
+            assert_eq!(format!("{:#}", e), want);
+        }
+        assert!(matches!(
+            parse_policy("pattern=(a"),
+            Err(PolicyParseError::InvalidPattern(..))
+        ));
+    }
 }
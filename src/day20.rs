@@ -1,19 +1,37 @@
-use eyre::{bail, eyre, Result};
-use std::convert::TryFrom;
+//! Day 20: Jurassic Jigsaw, assemble the image from its tiles and find the sea monsters.
+
+use crate::{
+    grid::{Grid, Orientation, View},
+    solution::Solution,
+    visualize::Frame,
+};
+use eyre::{bail, ensure, eyre, Result, WrapErr};
 use std::fmt::Debug;
-use tracing::{debug, info, instrument};
+use tracing::debug;
 
-#[instrument]
-pub fn run() -> Result<()> {
-    let tileset = TileSet::parse(include_str!("../data/day20.txt"))?;
-    info!(t = ?tileset.tiles.len());
-    let corner_product = tileset.find_corner_product();
-    info!(corner_product);
+/// Multiplies the ids of the corner tiles, and then counts the rough water that is not
+/// part of a sea monster.
+pub struct Day20;
 
-    let part_2 = tileset.find_part_2()?;
-    info!(part_2);
+impl Solution for Day20 {
+    type Input = TileSet;
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-    Ok(())
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let tileset = TileSet::parse(input)?;
+        debug!(t = ?tileset.tiles.len());
+
+        Ok(tileset)
+    }
+
+    fn part_1(&self, tileset: &Self::Input) -> Result<Self::Answer1> {
+        Ok(tileset.find_corner_product())
+    }
+
+    fn part_2(&self, tileset: &Self::Input) -> Result<Self::Answer2> {
+        tileset.find_part_2()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -35,9 +53,10 @@ impl Direction {
     }
 }
 
+/// A 10 by 10 tile of the image, with the ids of its edges read in both directions.
 #[derive(Clone, PartialEq)]
-struct Tile {
-    data: [bool; 100],
+pub struct Tile {
+    grid: Grid<bool>,
     id: u64,
     edge_ids: [u16; 4],         // up, right, down, left
     flipped_edge_ids: [u16; 4], // up, right, down, left
@@ -46,14 +65,10 @@ impl Debug for Tile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "\nid={}", self.id)?;
 
-        for (i, v) in self.data.iter().enumerate() {
-            if i % 10 == 0 {
-                writeln!(f)?;
-            }
-            if *v {
-                write!(f, "#")?;
-            } else {
-                write!(f, ".")?;
+        for row in self.grid.rows() {
+            writeln!(f)?;
+            for &v in row {
+                write!(f, "{}", if v { '#' } else { '.' })?;
             }
         }
 
@@ -63,56 +78,6 @@ impl Debug for Tile {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-struct Orientation {
-    flipped: bool, // we assume that we first rotate to the correct possition and then flip
-    rotation: usize, // in range 0..=3
-}
-
-impl Orientation {
-    fn get_indexer(&self, side: i16) -> Indexer {
-        let (start, row_step, col_step) = match (self.rotation, self.flipped) {
-            (0, false) => (0, side, 1),                   // (0, 10, 1)
-            (1, false) => (side - 1, -1, side),           // (9, -1, 10)
-            (2, false) => ((side * side) - 1, -side, -1), // (99, -10, -1)
-            (3, false) => (side * (side - 1), 1, -side),  // (90, 1, -10)
-            (0, true) => (side - 1, side, -1),            // (9, 10, -1)
-            (1, true) => ((side * side) - 1, -1, -side),  // (99, -1, -10)
-            (2, true) => (side * (side - 1), -side, 1),   // (90, -10, 1)
-            (3, true) => (0, 1, side),                    // (0, 1, 10)
-            c => panic!("bad rotation/flipped: {:?}", c),
-        };
-        Indexer::new(start, row_step, col_step)
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Indexer {
-    start: i16,
-    row_step: i16,
-    col_step: i16,
-}
-
-impl Indexer {
-    fn new(start: i16, row_step: i16, col_step: i16) -> Self {
-        Self {
-            start,
-            row_step,
-            col_step,
-        }
-    }
-
-    fn index(&self, row: usize, col: usize) -> usize {
-        (self.start as isize
-            + (self.row_step as isize * row as isize)
-            + (self.col_step as isize * col as isize)) as usize
-    }
-
-    fn iter(self, side: usize) -> impl Iterator<Item = usize> {
-        (0..side).flat_map(move |row| (0..side).map(move |col| self.index(row, col)))
-    }
-}
-
 #[derive(Clone, Copy, PartialEq, Debug)]
 struct OrientedTile<'a> {
     tile: &'a Tile,
@@ -120,13 +85,13 @@ struct OrientedTile<'a> {
 }
 
 impl<'a> OrientedTile<'a> {
-    fn new_with_id_in_direction(tile: &'a Tile, id: u16, direction: Direction) -> Self {
+    fn new_with_id_in_direction(tile: &'a Tile, id: u16, direction: Direction) -> Result<Self> {
         let (flipped, index) = if let Some(index) = tile.edge_ids.iter().position(|&x| x == id) {
             (false, index)
         } else if let Some(index) = tile.flipped_edge_ids.iter().position(|&x| x == id) {
             (true, index)
         } else {
-            todo!()
+            bail!("tile {} has no edge with id {}", tile.id, id);
         };
 
         let direction_index = direction.into_index();
@@ -140,10 +105,10 @@ impl<'a> OrientedTile<'a> {
             rotation = (rotation + 2).rem_euclid(4);
         }
 
-        OrientedTile {
+        Ok(OrientedTile {
             tile,
             orientation: Orientation { flipped, rotation },
-        }
+        })
     }
 
     fn get_id_in_direction(&self, direction: Direction) -> u16 {
@@ -188,103 +153,97 @@ impl<'a> OrientedTile<'a> {
         self.get_id_in_direction(Direction::Left)
     }
 
-    fn get_indexer(&self) -> Indexer {
-        self.orientation.get_indexer(10)
+    fn view(&self) -> View<'a, bool> {
+        self.tile.grid.view(self.orientation)
     }
 
+    /// The index in the rows of the tile of `row`, `col` in the oriented tile.
     #[cfg(test)]
     fn get_index_at_pos(&self, row: usize, col: usize) -> usize {
-        self.get_indexer().index(row, col)
-    }
-
-    // rotation:
-    // 0 -> row: x, col 1..=8
-    // 1 -> row 1..=8, col: x
-    // 2 -> row: 8 - x, col 8..=1
-    // 3 -> row: 8..=1, col: 8 - x
-    fn row_iter(&self, row: usize) -> impl Iterator<Item = &bool> {
-        let indexer = self.get_indexer();
-        (1..=8).map(move |col| &self.tile.data[indexer.index(row, col)])
+        let (x, y) = self.view().source(col, row).expect("inside the tile");
+        y * 10 + x
     }
 }
 
-#[instrument(level = "debug", skip(data))]
-fn calc_edge_id(data: &[bool], start: isize, step: isize) -> Result<u16> {
-    let mut id = 0;
-
-    for n in 0..10 {
-        let i = start + (n * step);
-        id <<= 1;
-        if *data
-            .get(usize::try_from(i)?)
-            .ok_or_else(|| eyre!("invalid index: {}", i))?
-        {
-            id += 1;
-        }
-    }
-
-    Ok(id)
+/// The id of the top edge of `grid` seen in `orientation`, read from the left with the
+/// first cell as the highest bit.
+fn edge_id(grid: &Grid<bool>, orientation: Orientation) -> u16 {
+    grid.view(orientation)
+        .row(0)
+        .fold(0, |id, &set| (id << 1) | set as u16)
 }
 
 impl Tile {
-    fn parse(input: &str) -> Result<Tile> {
-        let mut iter = input.lines();
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Parses a `Tile <id>:` header followed by 10 rows of `#` and `.`.
+    pub fn parse(input: &str) -> Result<Tile> {
+        let (header, rows) = input
+            .split_once('\n')
+            .ok_or_else(|| eyre!("unexpected end of input"))?;
 
-        let id = iter
-            .next()
-            .ok_or_else(|| eyre!("unexpected end of input"))?
+        let id = header
             .trim_start_matches("Tile ")
             .trim_end_matches(':')
             .parse::<u64>()?;
 
         debug!(id);
 
-        let mut data = [false; 100];
-
-        for (i, row) in iter.enumerate() {
-            for (j, c) in row.chars().enumerate() {
-                match c {
-                    '#' => data[j + (i * 10)] = true,
-                    '.' => (),
-                    c => bail!("invalid char: {}", c),
-                }
-            }
-        }
-
-        assert_eq!(data.len(), 100);
+        let grid = Grid::parse(rows.trim_end(), |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .wrap_err_with(|| format!("invalid tile {}", id))?;
+        ensure!(
+            grid.width() == 10 && grid.height() == 10,
+            "tile {} is {}x{}, expected 10x10",
+            id,
+            grid.width(),
+            grid.height()
+        );
 
+        // turning the tile counter clock wise brings the next edge to the top
         let edge_ids = [
             // clock wise
-            calc_edge_id(&data, 0, 1)?,    //  up
-            calc_edge_id(&data, 9, 10)?,   //  right
-            calc_edge_id(&data, 99, -1)?,  //  down
-            calc_edge_id(&data, 90, -10)?, //  left
+            edge_id(&grid, Orientation::new(0, false)), //  up
+            edge_id(&grid, Orientation::new(1, false)), //  right
+            edge_id(&grid, Orientation::new(2, false)), //  down
+            edge_id(&grid, Orientation::new(3, false)), //  left
         ];
 
         let flipped_edge_ids = [
             // counter clock wise
-            calc_edge_id(&data, 9, -1)?,   //  up
-            calc_edge_id(&data, 99, -10)?, //  right
-            calc_edge_id(&data, 90, 1)?,   //  down
-            calc_edge_id(&data, 0, 10)?,   //  left
+            edge_id(&grid, Orientation::new(0, true)), //  up
+            edge_id(&grid, Orientation::new(1, true)), //  right
+            edge_id(&grid, Orientation::new(2, true)), //  down
+            edge_id(&grid, Orientation::new(3, true)), //  left
         ];
 
         Ok(Self {
             id,
-            data,
+            grid,
             edge_ids,
             flipped_edge_ids,
         })
     }
 }
 
+/// The tiles of the image, in no particular order.
 #[derive(Debug)]
-struct TileSet {
+pub struct TileSet {
     tiles: Vec<Tile>,
 }
 
 impl TileSet {
-    fn get_grid_size(&self) -> usize {
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// The number of tiles on each side of the assembled image.
+    pub fn get_grid_size(&self) -> usize {
         for n in 1..=12 {
             if n * n == self.tiles.len() {
                 return n;
@@ -293,7 +252,8 @@ impl TileSet {
         panic!("bad grid?!");
     }
 
-    fn parse(input: &str) -> Result<Self> {
+    /// Parses the tiles, separated by blank lines.
+    pub fn parse(input: &str) -> Result<Self> {
         let tiles = input
             .split("\n\n")
             .map(Tile::parse)
@@ -309,7 +269,8 @@ impl TileSet {
         })
     }
 
-    fn is_corner(&self, tile: &Tile) -> bool {
+    /// Whether only two of the edges of `tile` match another tile.
+    pub fn is_corner(&self, tile: &Tile) -> bool {
         let mut n = 0;
 
         if self.find_match(tile, tile.edge_ids[0]).next().is_some() {
@@ -331,7 +292,8 @@ impl TileSet {
         n == 2
     }
 
-    fn find_corner_product(&self) -> u64 {
+    /// The product of the ids of the four corner tiles.
+    pub fn find_corner_product(&self) -> u64 {
         self.tiles
             .iter()
             .filter_map(|t| if self.is_corner(t) { Some(t.id) } else { None })
@@ -346,7 +308,25 @@ impl TileSet {
         })
     }
 
-    fn find_part_2(&self) -> Result<u64> {
+    /// Assembles the image and counts the `#` that are not part of a sea monster.
+    pub fn find_part_2(&self) -> Result<u64> {
+        let image = image(&self.assemble()?, self.get_grid_size());
+
+        let mut sea = MonsterSea::new(&image);
+
+        let monster_count = sea.search_for_mosters();
+        debug!("monster count: {}", monster_count);
+
+        let storm_count = image.iter().filter(|&&a| a).count() - (monster_count * MONSTER.len());
+        debug!("storm count: {}", storm_count);
+
+        debug!("sea:\n{}", sea.render());
+
+        Ok(storm_count as u64)
+    }
+
+    /// The tiles turned to fit together, row by row from the top left.
+    fn assemble(&self) -> Result<Vec<OrientedTile<'_>>> {
         let start = self
             .tiles
             .iter()
@@ -396,7 +376,7 @@ impl TileSet {
             debug!(row, col = 0, id = tile.id);
 
             let mut current =
-                OrientedTile::new_with_id_in_direction(tile, row_start_edge_id, Direction::Up);
+                OrientedTile::new_with_id_in_direction(tile, row_start_edge_id, Direction::Up)?;
 
             row_start_skip_id = current.tile.id;
             row_start_edge_id = current.get_id_in_direction(Direction::Down);
@@ -422,7 +402,7 @@ impl TileSet {
 
                 debug!(row, col, id = tile.id);
 
-                current = OrientedTile::new_with_id_in_direction(tile, edge_id, Direction::Left);
+                current = OrientedTile::new_with_id_in_direction(tile, edge_id, Direction::Left)?;
 
                 if row > 0 {
                     let above = rotated_tiles
@@ -437,107 +417,148 @@ impl TileSet {
             }
         }
 
-        let mut all = Vec::<bool>::new();
-
-        for i in 0..grid_size {
-            let tiles = rotated_tiles.iter().skip(i * grid_size).take(grid_size);
-            for row in 1..=8 {
-                for tile in tiles.clone() {
-                    all.extend(tile.row_iter(row));
-                }
-            }
-        }
+        Ok(rotated_tiles)
+    }
+}
 
-        let mut sea = MonsterSea::new(&all, grid_size * 8);
+/// The image made of the assembled `tiles` without their borders.
+fn image(tiles: &[OrientedTile<'_>], grid_size: usize) -> Grid<bool> {
+    let side = grid_size * 8;
+    Grid::from_fn(side, side, |x, y| {
+        let tile = &tiles[(y / 8) * grid_size + (x / 8)];
+        *tile
+            .view()
+            .get((x % 8) + 1, (y % 8) + 1)
+            .expect("inside the tile")
+    })
+}
 
-        let monster_count = sea.search_for_mosters();
-        debug!("monster count: {}", monster_count);
+/// The image assembled one tile at a time, followed by the search for the sea monsters
+/// in each orientation until they are found, marked as `O`.
+pub fn frames(input: &str) -> Result<Vec<Frame>> {
+    let tileset = TileSet::parse(input)?;
+    let grid_size = tileset.get_grid_size();
+    let tiles = tileset.assemble()?;
+    let image = image(&tiles, grid_size);
+
+    let mut frames: Vec<Frame> = (1..=tiles.len())
+        .map(|placed| {
+            let rows: Vec<String> = (0..image.height())
+                .map(|y| {
+                    (0..image.width())
+                        .map(|x| match image[(x, y)] {
+                            _ if (y / 8) * grid_size + (x / 8) >= placed => ' ',
+                            true => '#',
+                            false => '.',
+                        })
+                        .collect()
+                })
+                .collect();
+            Frame::new(
+                format!("placed {} of {} tiles", placed, tiles.len()),
+                rows.join("\n"),
+            )
+        })
+        .collect();
 
-        let storm_count = all.iter().filter(|&&a| a).count() - (monster_count * 15);
-        debug!("storm count: {}", storm_count);
+    let rough = image.iter().filter(|&&a| a).count();
+    for orientation in search_order() {
+        let mut sea = MonsterSea::new(&image);
+        sea.orientation = orientation;
+        let monster_count = sea.count_monsters();
 
-        sea.print_sea();
+        let mut title = format!(
+            "rotation {}, flipped {}: {} monsters",
+            orientation.rotation, orientation.flipped, monster_count
+        );
+        if monster_count > 0 {
+            title += &format!(", {} rough water", rough - monster_count * MONSTER.len());
+        }
+        frames.push(Frame::new(title, sea.render()));
 
-        Ok(storm_count as u64)
+        if monster_count > 0 {
+            break;
+        }
     }
+
+    Ok(frames)
 }
 
+/// The orientations the image is searched in for sea monsters, in order.
+fn search_order() -> impl Iterator<Item = Orientation> {
+    [true, false]
+        .iter()
+        .flat_map(|&flip| (0..=3).rev().map(move |i| Orientation::new(i, flip)))
+}
+
+/// The cells of a sea monster, relative to the top left of the 20 by 3 box around it.
+///
+/// ```text
+///                   #
+/// #    ##    ##    ###
+///  #  #  #  #  #  #
+/// ```
+const MONSTER: [(usize, usize); 15] = [
+    (18, 0),
+    (0, 1),
+    (5, 1),
+    (6, 1),
+    (11, 1),
+    (12, 1),
+    (17, 1),
+    (18, 1),
+    (19, 1),
+    (1, 2),
+    (4, 2),
+    (7, 2),
+    (10, 2),
+    (13, 2),
+    (16, 2),
+];
+
 #[derive(Clone, PartialEq, Debug)]
 struct MonsterSea<'a> {
-    data: &'a [bool],
-    width: usize,
+    image: &'a Grid<bool>,
     orientation: Orientation,
-    monster_indexes: Vec<usize>,
+    monster_positions: Vec<(usize, usize)>, // in the image
 }
 
 impl<'a> MonsterSea<'a> {
-    fn new(data: &'a [bool], width: usize) -> Self {
-        assert_eq!(data.len(), width * width);
+    fn new(image: &'a Grid<bool>) -> Self {
+        assert_eq!(image.width(), image.height());
         Self {
-            data,
-            width,
-            orientation: Orientation {
-                flipped: false,
-                rotation: 0,
-            },
-            monster_indexes: Vec::new(),
+            image,
+            orientation: Orientation::default(),
+            monster_positions: Vec::new(),
         }
     }
 
-    fn find_monster(&mut self, start: usize, indexer: Indexer) -> bool {
-        let c = indexer.col_step as isize;
-        let r = indexer.row_step as isize;
-        let mut index = start as isize;
-
-        let mut monster_indexes = vec![];
-
-        // for delta in [0, w - 18, 5, 1, 5, 1, 5, 1, 1, w - 18, 3, 3, 3, 3, 3].iter() {
-        for (row_delta, col_delta) in [
-            (0, 0),
-            (1, -18),
-            (0, 5),
-            (0, 1),
-            (0, 5),
-            (0, 1),
-            (0, 5),
-            (0, 1),
-            (0, 1),
-            (1, -18),
-            (0, 3),
-            (0, 3),
-            (0, 3),
-            (0, 3),
-            (0, 3),
-        ]
-        .iter()
-        {
-            index += (row_delta * r) + (col_delta * c);
+    fn find_monster(&mut self, x: usize, y: usize) -> bool {
+        let view = self.image.view(self.orientation);
 
-            if self.data.get(index as usize) != Some(&true) {
-                return false;
-            }
+        let positions = MONSTER
+            .iter()
+            .map(|&(dx, dy)| view.source(x + dx, y + dy))
+            .collect::<Option<Vec<_>>>();
 
-            monster_indexes.push(index as usize);
+        match positions {
+            Some(positions) if positions.iter().all(|&pos| self.image[pos]) => {
+                self.monster_positions.extend(positions);
+                true
+            }
+            _ => false,
         }
-
-        self.monster_indexes.extend(monster_indexes.into_iter());
-
-        true
     }
 
     fn count_monsters(&mut self) -> usize {
-        let indexer = self.orientation.get_indexer(self.width as _);
+        let view = self.image.view(self.orientation);
         let mut monster_count = 0;
-        for row in 0..(self.width - 2) {
-            // for row in 0..self.width {
-            // a monster requires 3 lines
-            for col in 18..(self.width - 1) {
-                // for col in 0..self.width {
-                // a monster requires 20 cols
-                let index = indexer.index(row, col);
-                if self.find_monster(index, indexer) {
+        // a monster requires 3 rows and 20 cols
+        for y in 0..view.height().saturating_sub(2) {
+            for x in 0..view.width().saturating_sub(19) {
+                if self.find_monster(x, y) {
                     monster_count += 1;
-                    debug!("found monster at: {}", index);
+                    debug!("found monster at: {:?}", (x, y));
                 }
             }
         }
@@ -546,50 +567,50 @@ impl<'a> MonsterSea<'a> {
     }
 
     fn search_for_mosters(&mut self) -> usize {
-        for &flip in [true, false].iter() {
-            for i in (0..=3).rev() {
-                self.orientation.rotation = i;
-                self.orientation.flipped = flip;
-                debug!(
-                    "looking for monsters in orientation: {:?}",
-                    self.orientation
-                );
-                let monster_count = self.count_monsters();
-                if monster_count > 0 {
-                    return monster_count;
-                }
+        for orientation in search_order() {
+            self.orientation = orientation;
+            debug!(
+                "looking for monsters in orientation: {:?}",
+                self.orientation
+            );
+            let monster_count = self.count_monsters();
+            if monster_count > 0 {
+                return monster_count;
             }
         }
 
         0
     }
 
-    fn print_sea(&self) {
-        let indexer = self.orientation.get_indexer(self.width as i16);
+    /// The sea with the monsters marked as `O`, one row per line.
+    fn render(&self) -> String {
+        let view = self.image.view(self.orientation);
+        let mut sea = String::new();
 
-        for (i, index) in indexer.iter(self.width).enumerate() {
-            if i % self.width == 0 {
-                println!();
+        for y in 0..view.height() {
+            if y > 0 {
+                sea.push('\n');
             }
-            if self.data[index] {
-                if self.monster_indexes.contains(&index) {
-                    print!("O");
+            for x in 0..view.width() {
+                let pos = view.source(x, y).expect("inside the view");
+                if !self.image[pos] {
+                    sea.push('.');
+                } else if self.monster_positions.contains(&pos) {
+                    sea.push('O');
                 } else {
-                    print!("#");
+                    sea.push('#');
                 }
-            } else {
-                print!(".");
             }
         }
 
-        println!();
+        sea
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tracing::debug;
+    use tracing::{debug, info};
 
     #[test]
     fn test_parse() -> Result<()> {
@@ -629,9 +650,9 @@ mod tests {
     }
 
     #[test]
-    fn test_new_with_id_in_direction() {
+    fn test_new_with_id_in_direction() -> Result<()> {
         let tile = &Tile {
-            data: [false; 100],
+            grid: Grid::new(10, 10, false),
             id: 1,
             edge_ids: [1, 2, 3, 4],
             flipped_edge_ids: [5, 6, 7, 8],
@@ -646,7 +667,7 @@ mod tests {
 
         for id in 1..=8 {
             for &d in directions.iter() {
-                let got_tile = OrientedTile::new_with_id_in_direction(tile, id, d);
+                let got_tile = OrientedTile::new_with_id_in_direction(tile, id, d)?;
                 let got_id = got_tile.get_id_in_direction(d);
                 assert_eq!(
                     id, got_id,
@@ -655,24 +676,30 @@ mod tests {
                 );
             }
         }
+
+        assert!(OrientedTile::new_with_id_in_direction(tile, 9, Direction::Up).is_err());
+
+        Ok(())
     }
 
     #[test]
     fn test_get_id_in_direction() {
         let tile = &Tile {
-            data: [false; 100],
+            grid: Grid::new(10, 10, false),
             id: 1,
             edge_ids: [1, 2, 3, 4],
             flipped_edge_ids: [5, 6, 7, 8],
         };
 
         let tests = vec![
-            (false, 0, 1, 2, 3, 4),
-            (false, 1, 2, 3, 4, 1),
-            (true, 0, 3, 6, 1, 8),
-            (true, 0, 5, 4, 7, 2),
-            (true, 0, 7, 8, 5, 6),
-            (true, 1, 8, 5, 6, 7),
+            (false, 0, 1, 2, 7, 8),
+            (false, 1, 2, 3, 8, 5),
+            (false, 2, 3, 4, 5, 6),
+            (false, 3, 4, 1, 6, 7),
+            (true, 0, 5, 8, 3, 2),
+            (true, 1, 6, 5, 4, 3),
+            (true, 2, 7, 6, 1, 4),
+            (true, 3, 8, 7, 2, 1),
         ];
 
         for (flipped, rotation, up, right, down, left) in tests {
@@ -709,7 +736,7 @@ mod tests {
             (true, 3, [32, 64, 4, 2]),
         ];
         for (want_flipped, want_rotation, want_ids) in tests {
-            let got = OrientedTile::new_with_id_in_direction(&tile, want_ids[0], Direction::Up);
+            let got = OrientedTile::new_with_id_in_direction(&tile, want_ids[0], Direction::Up)?;
             info!(want_flipped, want_rotation, ?want_ids, ?got);
 
             assert_eq!(got.orientation.flipped, want_flipped);
@@ -717,17 +744,17 @@ mod tests {
 
             assert_eq!(
                 got,
-                OrientedTile::new_with_id_in_direction(&tile, want_ids[1], Direction::Right)
+                OrientedTile::new_with_id_in_direction(&tile, want_ids[1], Direction::Right)?
             );
 
             assert_eq!(
                 got,
-                OrientedTile::new_with_id_in_direction(&tile, want_ids[2], Direction::Down)
+                OrientedTile::new_with_id_in_direction(&tile, want_ids[2], Direction::Down)?
             );
 
             assert_eq!(
                 got,
-                OrientedTile::new_with_id_in_direction(&tile, want_ids[3], Direction::Left)
+                OrientedTile::new_with_id_in_direction(&tile, want_ids[3], Direction::Left)?
             );
 
             assert_eq!(got.up(), want_ids[0], "up");
@@ -773,7 +800,7 @@ mod tests {
         ];
         for (want_flipped, want_rotation, want_ids, want_indexes_row_0, want_indexes_row_1) in tests
         {
-            let got = OrientedTile::new_with_id_in_direction(&tile, want_ids[0], Direction::Up);
+            let got = OrientedTile::new_with_id_in_direction(&tile, want_ids[0], Direction::Up)?;
             info!(want_flipped, want_rotation, ?want_ids, ?got);
 
             assert_eq!(got.orientation.flipped, want_flipped);
@@ -793,7 +820,7 @@ mod tests {
             assert_eq!(got.get_index_at_pos(1, 2), want_indexes_row_1[2]);
             assert_eq!(got.get_index_at_pos(1, 9), want_indexes_row_1[3]);
 
-            let row_0 = got.row_iter(0).collect::<Vec<_>>();
+            let row_0 = got.view().row(0).collect::<Vec<_>>();
             debug!(?row_0);
         }
 
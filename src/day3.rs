@@ -1,45 +1,346 @@
-pub fn run() {
-    let input = include_str!("../data/day3.txt")
-        .trim()
-        .split_terminator('\n')
-        .collect::<Vec<_>>();
-
-    let a: (usize, usize) = input.iter().skip(1).fold((0, 0), |(mut n, x), input| {
-        let x = (x + 3) % input.len();
-
-        let tree = input.get(x..=x).expect("should not go out of bounds");
-        if tree == "#" {
-            n += 1;
+//! Day 3: Toboggan Trajectory, count the trees hit going down a slope.
+
+use crate::{grid::Grid, solution::Solution};
+use eyre::{ensure, Result, WrapErr};
+use std::{fmt, num::ParseIntError, ops::RangeInclusive, str::FromStr};
+use thiserror::Error;
+
+/// Counts the trees on [`PART_1_SLOPE`], and then multiplies the counts of
+/// [`PART_2_SLOPES`].
+pub struct Day3;
+
+/// How far the toboggan goes at a time, `down` is at least 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Slope {
+    pub right: usize,
+    pub down: usize,
+}
+
+impl Slope {
+    pub const fn new(right: usize, down: usize) -> Self {
+        Self { right, down }
+    }
+}
+
+pub const PART_1_SLOPE: Slope = Slope::new(3, 1);
+
+pub const PART_2_SLOPES: [Slope; 5] = [
+    Slope::new(1, 1),
+    Slope::new(3, 1),
+    Slope::new(5, 1),
+    Slope::new(7, 1),
+    Slope::new(1, 2),
+];
+
+impl Solution for Day3 {
+    type Input = Grid<Square>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(evaluate(input, PART_1_SLOPE)?.trees)
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        PART_2_SLOPES
+            .iter()
+            .map(|&slope| Ok(evaluate(input, slope)?.trees))
+            .product()
+    }
+}
+
+/// A square of the map, `.` is open and `#` a tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Square {
+    Open,
+    Tree,
+}
+
+impl Square {
+    fn parse(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Square::Open),
+            '#' => Some(Square::Tree),
+            _ => None,
         }
+    }
+}
 
-        (n, x)
-    });
-
-    let b = calc(&input, 1, 1)
-        * calc(&input, 3, 1)
-        * calc(&input, 5, 1)
-        * calc(&input, 7, 1)
-        * calc(&input, 1, 2);
-
-    println!("day 3: {}, {}", a.0, b);
-}
-
-fn calc(input: &[&str], right: usize, down: usize) -> usize {
-    input
-        .iter()
-        .fold((0, 0, 0), |(mut n, mut x, mut y), input| {
-            if y == down {
-                x = (x + right) % input.len();
-                let tree = input.get(x..=x).expect("should not go out of bounds");
-                if tree == "#" {
-                    n += 1;
-                }
-                y = 1;
-            } else {
-                y += 1;
-            }
-            // dbg!((n, x, y, input));
-            (n, x, y)
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Square::Open => write!(f, "."),
+            Square::Tree => write!(f, "#"),
+        }
+    }
+}
+
+/// Parses the map, one row of `.` and `#` per line, all rows have the same width.
+pub fn parse_input(input: &str) -> Result<Grid<Square>> {
+    Grid::parse(input, Square::parse).wrap_err("invalid map")
+}
+
+#[derive(Error, Debug)]
+pub enum SlopeParseError {
+    #[error("invalid slope: '{0}', expected '<right>,<down>'")]
+    InvalidSlope(String),
+    #[error("invalid step: '{0}'")]
+    InvalidStep(String, #[source] ParseIntError),
+    #[error("invalid slope: '{0}', down has to be at least 1")]
+    NotDown(String),
+}
+
+impl FromStr for Slope {
+    type Err = SlopeParseError;
+
+    /// Parses `<right>,<down>`, e.g. `3,1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (right, down) = s
+            .split_once(',')
+            .ok_or_else(|| SlopeParseError::InvalidSlope(s.to_string()))?;
+        let step = |step: &str| {
+            usize::from_str(step.trim())
+                .map_err(|e| SlopeParseError::InvalidStep(step.to_string(), e))
+        };
+
+        let slope = Slope::new(step(right)?, step(down)?);
+        if slope.down == 0 {
+            return Err(SlopeParseError::NotDown(s.to_string()));
+        }
+
+        Ok(slope)
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.right, self.down)
+    }
+}
+
+/// What the toboggan passes going down one slope.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlopeStats {
+    pub slope: Slope,
+    /// The squares passed after the start, the start is not counted
+    pub squares: usize,
+    pub trees: usize,
+    /// The row of the first tree hit, counting from 0, if any
+    pub first_tree: Option<usize>,
+}
+
+impl SlopeStats {
+    /// The fraction of the squares passed that are trees.
+    pub fn density(&self) -> f64 {
+        if self.squares == 0 {
+            0.0
+        } else {
+            self.trees as f64 / self.squares as f64
+        }
+    }
+}
+
+/// Goes down the slope from the top left until the bottom of the map, which repeats to the
+/// right. Fails on an empty map, or if `slope.down` is 0 and the toboggan would never reach
+/// the bottom.
+pub fn evaluate(input: &Grid<Square>, slope: Slope) -> Result<SlopeStats> {
+    ensure!(
+        input.width() > 0 && input.height() > 0,
+        "the map is empty, there is no slope to go down"
+    );
+    ensure!(
+        slope.down > 0,
+        "invalid slope: '{}', down has to be at least 1",
+        slope
+    );
+
+    // the map repeats, so going right a whole width at a time is the same as not moving
+    let right = slope.right % input.width();
+    let rows: Vec<usize> = (0..input.height())
+        .step_by(slope.down)
+        .enumerate()
+        .skip(1)
+        .filter(|&(i, y)| {
+            let x = (i * right) % input.width();
+            input[(x, y)] == Square::Tree
         })
-        .0
+        .map(|(_, y)| y)
+        .collect();
+
+    Ok(SlopeStats {
+        slope,
+        squares: (input.height() - 1) / slope.down,
+        trees: rows.len(),
+        first_tree: rows.first().copied(),
+    })
+}
+
+/// The stats of every slope going `rights` right and `downs` down, ordered by slope.
+pub fn sweep(
+    input: &Grid<Square>,
+    rights: RangeInclusive<usize>,
+    downs: RangeInclusive<usize>,
+) -> Result<Vec<SlopeStats>> {
+    let downs = (*downs.start()).max(1)..=*downs.end();
+
+    rights
+        .flat_map(|right| downs.clone().map(move |down| Slope::new(right, down)))
+        .map(|slope| evaluate(input, slope))
+        .collect()
+}
+
+/// The slope that hits the fewest trees, the first one on a tie.
+pub fn fewest_trees(stats: &[SlopeStats]) -> Option<&SlopeStats> {
+    stats.iter().min_by_key(|stats| stats.trees)
+}
+
+/// The slope that hits the most trees, the first one on a tie.
+pub fn most_trees(stats: &[SlopeStats]) -> Option<&SlopeStats> {
+    stats.iter().rev().max_by_key(|stats| stats.trees)
+}
+
+/// The stats as a table, one slope per row.
+pub fn format_table(stats: &[SlopeStats]) -> String {
+    let mut lines = vec![format!(
+        "{:>5}  {:>4}  {:>7}  {:>5}  {:>7}  {:>10}",
+        "right", "down", "squares", "trees", "density", "first tree"
+    )];
+
+    for stats in stats {
+        let first_tree = match stats.first_tree {
+            Some(row) => format!("line {}", row + 1),
+            None => "-".to_string(),
+        };
+        lines.push(format!(
+            "{:>5}  {:>4}  {:>7}  {:>5}  {:>7.3}  {:>10}",
+            stats.slope.right,
+            stats.slope.down,
+            stats.squares,
+            stats.trees,
+            stats.density(),
+            first_tree
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    #[test]
+    fn test_evaluate() -> Result<()> {
+        let input = parse_input(EXAMPLE)?;
+
+        assert_eq!(Day3.part_1(&input)?, 7);
+        assert_eq!(
+            evaluate(&input, Slope::new(3, 1))?,
+            SlopeStats {
+                slope: Slope::new(3, 1),
+                squares: 10,
+                trees: 7,
+                first_tree: Some(2),
+            }
+        );
+        assert_eq!(Day3.part_2(&input)?, 336);
+
+        let trees: Vec<usize> = PART_2_SLOPES
+            .iter()
+            .map(|&slope| Ok(evaluate(&input, slope)?.trees))
+            .collect::<Result<_>>()?;
+        assert_eq!(trees, vec![2, 7, 3, 4, 2]);
+
+        assert_eq!(evaluate(&input, Slope::new(1, 2))?.squares, 5);
+        assert_eq!(evaluate(&input, Slope::new(1, 20))?.squares, 0);
+
+        assert_eq!(
+            evaluate(&input, Slope::new(usize::MAX, 1))?,
+            SlopeStats {
+                slope: Slope::new(usize::MAX, 1),
+                ..evaluate(&input, Slope::new(usize::MAX % 11, 1))?
+            },
+            "a step of a whole width is the same as none"
+        );
+        assert_eq!(
+            evaluate(&input, Slope::new(3, 0)).unwrap_err().to_string(),
+            "invalid slope: '3,0', down has to be at least 1"
+        );
+        assert_eq!(
+            evaluate(&Grid::new(0, 0, Square::Open), Slope::new(3, 1))
+                .unwrap_err()
+                .to_string(),
+            "the map is empty, there is no slope to go down"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sweep() -> Result<()> {
+        let input = parse_input(EXAMPLE)?;
+        let stats = sweep(&input, 1..=7, 0..=2)?;
+
+        assert_eq!(stats.len(), 14);
+        assert_eq!(stats[0].slope, Slope::new(1, 1));
+        assert_eq!(stats[13].slope, Slope::new(7, 2));
+        assert_eq!(
+            fewest_trees(&stats).map(|s| s.slope),
+            Some(Slope::new(5, 2))
+        );
+        assert_eq!(most_trees(&stats).map(|s| s.slope), Some(Slope::new(3, 1)));
+        assert_eq!(fewest_trees(&[]), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_slope() {
+        assert_eq!(Slope::from_str("3,1").ok(), Some(Slope::new(3, 1)));
+        assert_eq!(Slope::from_str(" 0 , 2").ok(), Some(Slope::new(0, 2)));
+
+        let tests = vec![
+            ("3", "invalid slope: '3', expected '<right>,<down>'"),
+            ("3,x", "invalid step: 'x': invalid digit found in string"),
+            ("3,0", "invalid slope: '3,0', down has to be at least 1"),
+        ];
+        for (input, want) in tests {
+            let e = eyre::Report::new(Slope::from_str(input).unwrap_err());
+            assert_eq!(format!("{:#}", e), want);
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let tests = vec![
+            (
+                "..#\n.x.",
+                "invalid map: invalid cell on line 2, column 2: 'x'",
+            ),
+            (
+                "..#\n....",
+                "invalid map: invalid row width on line 2: 4, expected 3",
+            ),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(format!("{:#}", parse_input(input).unwrap_err()), want);
+        }
+    }
 }
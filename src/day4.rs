@@ -1,29 +1,60 @@
+//! Day 4: Passport Processing, validate the fields of the passports.
+
+use crate::{
+    parse::{ParseError, Span, WithSnippet},
+    solution::Solution,
+};
+use eyre::Result;
 use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Counts the passports that have the required fields, and then the ones where every
+/// field is also valid.
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<HashMap<String, String>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-pub fn run() {
-    let input = include_str!("../data/day4.txt");
-    let a = check_input(input);
-    let b = check_input2(input);
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(check_input(input))
+    }
 
-    println!("day 4: {}, {}", a, b);
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(check_input2(input))
+    }
 }
 
-fn parse_input(input: &str) -> Vec<HashMap<&str, &str>> {
-    input
-        .trim()
-        .split_terminator("\n\n")
-        .map(|a| {
-            a.split_whitespace()
-                .map(|b| {
-                    let mut b = b.splitn(2, ':');
-                    (b.next().unwrap(), b.next().unwrap())
-                })
-                .collect::<HashMap<_, _>>()
-        })
-        .collect::<Vec<_>>()
+#[derive(Error, Debug, PartialEq)]
+pub enum PassportParseError {
+    #[error("invalid field: '{0}', expected '<key>:<value>'")]
+    InvalidField(String),
 }
 
-fn check_input(input: &str) -> usize {
+fn parse_field(field: Span) -> Result<(String, String), ParseError> {
+    let (key, value) = field
+        .split_once(":")
+        .map_err(|_| field.error_other(PassportParseError::InvalidField(field.as_str().into())))?;
+
+    Ok((key.as_str().into(), value.as_str().into()))
+}
+
+/// Passports are separated by blank lines and their fields can span several lines.
+pub fn parse_input(input: &str) -> Result<Vec<HashMap<String, String>>> {
+    Span::new(input)
+        .records()
+        .map(|passport| passport.words().map(parse_field).collect())
+        .collect::<Result<_, _>>()
+        .with_snippet()
+}
+
+/// Counts the passports that have every required field, `cid` is optional.
+pub fn check_input(input: &[HashMap<String, String>]) -> usize {
     let mut want = HashSet::new();
     want.insert("byr");
     want.insert("ecl");
@@ -33,10 +64,8 @@ fn check_input(input: &str) -> usize {
     want.insert("iyr");
     want.insert("pid");
 
-    let input = parse_input(input);
-
-    input.into_iter().fold(0, |mut n, a| {
-        let a = a.into_iter().map(|a| a.0).collect::<HashSet<_>>();
+    input.iter().fold(0, |mut n, a| {
+        let a = a.keys().map(String::as_str).collect::<HashSet<_>>();
 
         if want.difference(&a).count() == 0 {
             n += 1;
@@ -46,8 +75,8 @@ fn check_input(input: &str) -> usize {
     })
 }
 
-fn check_input2(input: &str) -> usize {
-    let input = parse_input(input);
+/// Counts the passports where every required field is present and valid.
+pub fn check_input2(input: &[HashMap<String, String>]) -> usize {
     let num = "1234567890";
     let hex = "abcdef";
 
@@ -61,48 +90,48 @@ fn check_input2(input: &str) -> usize {
     ecls.insert("oth");
 
     input
-        .into_iter()
-        .filter_map(|mut a| {
-            let byr = a.remove("byr")?.parse::<usize>().ok()?;
-            if byr < 1920 || byr > 2002 {
+        .iter()
+        .filter_map(|a| {
+            let byr = a.get("byr")?.parse::<usize>().ok()?;
+            if !(1920..=2002).contains(&byr) {
                 return None;
             }
 
-            let iyr = a.remove("iyr")?.parse::<usize>().ok()?;
-            if iyr < 2010 || iyr > 2020 {
+            let iyr = a.get("iyr")?.parse::<usize>().ok()?;
+            if !(2010..=2020).contains(&iyr) {
                 return None;
             }
 
-            let eyr = a.remove("eyr")?.parse::<usize>().ok()?;
-            if eyr < 2020 || eyr > 2030 {
+            let eyr = a.get("eyr")?.parse::<usize>().ok()?;
+            if !(2020..=2030).contains(&eyr) {
                 return None;
             }
 
-            let hgt = a.remove("hgt")?;
+            let hgt = a.get("hgt")?;
             if let Some(hgt) = hgt.strip_suffix("cm") {
                 let hgt = hgt.parse::<usize>().ok()?;
-                if hgt < 150 || hgt > 193 {
+                if !(150..=193).contains(&hgt) {
                     return None;
                 }
             } else if let Some(hgt) = hgt.strip_suffix("in") {
                 let hgt = hgt.parse::<usize>().ok()?;
-                if hgt < 59 || hgt > 76 {
+                if !(59..=76).contains(&hgt) {
                     return None;
                 }
             } else {
                 return None;
             }
 
-            let hcl = a.remove("hcl")?.strip_prefix("#")?;
+            let hcl = a.get("hcl")?.strip_prefix("#")?;
             if hcl.len() != 6 || hcl.chars().any(|c| !num.contains(c) && !hex.contains(c)) {
                 return None;
             }
 
-            if !ecls.contains(a.remove("ecl")?) {
+            if !ecls.contains(a.get("ecl")?.as_str()) {
                 return None;
             }
 
-            let pid = a.remove("pid")?;
+            let pid = a.get("pid")?;
             if pid.len() != 9 || pid.chars().any(|c| !num.contains(c)) {
                 return None;
             }
@@ -118,16 +147,30 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_day4() {
-        let got = check_input(include_str!("../data/day4_test.txt"));
+    fn test_day4() -> Result<()> {
+        let got = check_input(&parse_input(include_str!("../data/day4_test.txt"))?);
 
         assert_eq!(got, 2);
+
+        Ok(())
     }
 
     #[test]
-    fn test_day4_part2() {
-        let got = check_input2(include_str!("../data/day4_test.txt"));
+    fn test_day4_part2() -> Result<()> {
+        let got = check_input2(&parse_input(include_str!("../data/day4_test.txt"))?);
 
         assert_eq!(got, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let err = parse_input("ecl:gry pid:860033327\neyr:2020\n\nhcl:#cfa07d byr\n").unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", err),
+            "invalid input on line 4, column 13: invalid field: 'byr', expected '<key>:<value>'"
+        );
     }
 }
@@ -1,27 +1,48 @@
+//! Day 5: Binary Boarding, decode the binary space partitioned boarding passes.
+
+use crate::{parse, solution::Solution};
+use eyre::{eyre, Result, WrapErr};
 use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Finds the highest seat id, and then the free seat that is ours.
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Vec<(usize, usize, usize)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
 
-pub fn run() {
-    let input = include_str!("../data/day5.txt");
-    let a = check_input(input).unwrap();
-    let b = check_input2(input).unwrap();
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        check_input(input).ok_or_else(|| eyre!("no boarding passes"))
+    }
 
-    println!("day 5: {}, {}", a, b);
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        check_input2(input).ok_or_else(|| eyre!("no free seat found"))
+    }
 }
 
-fn parse_input(input: &str) -> Vec<(usize, usize, usize)> {
-    input
-        .trim()
-        .split_terminator('\n')
-        .map(parse_pass)
-        .collect::<Vec<_>>()
+/// Parses one boarding pass per line into `(row, seat, seat id)`.
+pub fn parse_input(input: &str) -> Result<Vec<(usize, usize, usize)>> {
+    parse::numbered_lines(input)
+        .map(|(number, pass)| {
+            parse_pass(pass).wrap_err_with(|| format!("invalid input on line {}", number))
+        })
+        .collect()
 }
 
-fn check_input(input: &str) -> Option<usize> {
-    parse_input(input).iter().map(|(_, _, id)| *id).max()
+/// The highest seat id.
+pub fn check_input(input: &[(usize, usize, usize)]) -> Option<usize> {
+    input.iter().map(|(_, _, id)| *id).max()
 }
 
-fn check_input2(input: &str) -> Option<usize> {
-    parse_input(input)
+/// The id of the only free seat in a row where every other seat is taken.
+pub fn check_input2(input: &[(usize, usize, usize)]) -> Option<usize> {
+    input
         .iter()
         .fold(
             HashMap::<usize, HashSet<usize>>::new(),
@@ -33,37 +54,50 @@ fn check_input2(input: &str) -> Option<usize> {
         .into_iter()
         .find_map(|(row, seats)| {
             if seats.len() == 7 {
-                for i in 0..=7 {
-                    if !seats.contains(&i) {
-                        return Some(seat_id(row, i));
-                    }
-                }
-                panic!("should find seat in row with only 7 taken seats");
+                (0..=7)
+                    .find(|i| !seats.contains(i))
+                    .map(|seat| seat_id(row, seat))
             } else {
                 None
             }
         })
 }
 
-fn parse_bsp(chars: &str, upper: char) -> usize {
-    chars.chars().fold(0, |mut n, c| {
+#[derive(Error, Debug, PartialEq)]
+pub enum BoardingPassParseError {
+    #[error("invalid boarding pass: '{0}', expected 7 of F/B followed by 3 of L/R")]
+    InvalidPass(String),
+}
+
+fn parse_bsp(chars: &str, lower: char, upper: char) -> Option<usize> {
+    chars.chars().try_fold(0, |mut n, c| {
         n <<= 1;
         if c == upper {
             n += 1;
+        } else if c != lower {
+            return None;
         }
-        n
+        Some(n)
     })
 }
 
-fn seat_id(row: usize, seat: usize) -> usize {
+/// Seat ids are `row * 8 + seat`.
+pub fn seat_id(row: usize, seat: usize) -> usize {
     (row << 3) + seat
 }
 
-fn parse_pass(pass: &str) -> (usize, usize, usize) {
+/// Parses a pass like `FBFBBFFRLR` into `(row, seat, seat id)`.
+pub fn parse_pass(pass: &str) -> Result<(usize, usize, usize), BoardingPassParseError> {
+    let invalid_pass = || BoardingPassParseError::InvalidPass(pass.to_string());
+
+    if pass.len() != 10 || !pass.is_ascii() {
+        return Err(invalid_pass());
+    }
+
     let (rows, seats) = pass.split_at(7);
-    let row = parse_bsp(rows, 'B');
-    let seat = parse_bsp(seats, 'R');
-    (row, seat, seat_id(row, seat))
+    let row = parse_bsp(rows, 'F', 'B').ok_or_else(invalid_pass)?;
+    let seat = parse_bsp(seats, 'L', 'R').ok_or_else(invalid_pass)?;
+    Ok((row, seat, seat_id(row, seat)))
 }
 
 #[cfg(test)]
@@ -72,7 +106,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_pass() {
+    fn test_parse_pass() -> Result<()> {
         let tt = vec![
             ("FBFBBFFRLR", 44, 5, 357),
             ("BFFFBBFRRR", 70, 7, 567),
@@ -81,17 +115,38 @@ mod tests {
         ];
 
         for (pass, row, seat, id) in tt {
-            let (got_row, got_col, got_id) = parse_pass(pass);
+            let (got_row, got_col, got_id) = parse_pass(pass)?;
             assert_eq!(row, got_row);
             assert_eq!(seat, got_col);
             assert_eq!(id, got_id);
         }
+
+        Ok(())
     }
 
     #[test]
-    fn test_day5() {
-        let got = check_input2(include_str!("../data/day5.txt")).unwrap();
+    fn test_parse_invalid() {
+        let tests = vec![
+            "FBFBBFFRL",
+            "FBFBBFFRLRR",
+            "FBFBBFFRLB",
+            "FBFBXFFRLR",
+            "FBFBBFFRLÅ",
+        ];
+
+        for pass in tests {
+            assert_eq!(
+                parse_pass(pass),
+                Err(BoardingPassParseError::InvalidPass(pass.to_string())),
+                "{}",
+                pass
+            );
+        }
 
-        assert_eq!(got, 696);
+        let err = parse_input("FBFBBFFRLR\nBFFFBBFRRX").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "invalid input on line 2: invalid boarding pass: 'BFFFBBFRRX', expected 7 of F/B followed by 3 of L/R"
+        );
     }
 }
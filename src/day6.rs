@@ -1,23 +1,70 @@
+//! Day 6: Custom Customs, count the questions answered by each group.
+
+use crate::{
+    parse::{ParseError, Span, WithSnippet},
+    solution::Solution,
+};
+use eyre::Result;
 use std::collections::HashSet;
+use thiserror::Error;
+
+/// Sums the questions anyone in a group answered, and then the ones everyone answered.
+pub struct Day6;
 
-pub fn run() {
-    let input = include_str!("../data/day6.txt");
-    let a = check_input(input);
-    let b = check_input2(input);
+impl Solution for Day6 {
+    type Input = Vec<Vec<HashSet<char>>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
 
-    println!("day 6: {}, {}", a, b);
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(check_input(input))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(check_input2(input))
+    }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<HashSet<char>>> {
-    input
-        .trim()
-        .split_terminator("\n\n")
-        .map(parse_goup)
-        .collect::<Vec<_>>()
+#[derive(Error, Debug, PartialEq)]
+pub enum AnswerParseError {
+    #[error("invalid answer: '{0}', expected a-z")]
+    InvalidAnswer(char),
+}
+
+fn parse_person(person: Span) -> Result<HashSet<char>, ParseError> {
+    person
+        .as_str()
+        .char_indices()
+        .map(|(i, c)| match c {
+            'a'..='z' => Ok(c),
+            _ => Err(person
+                .slice(i..i + c.len_utf8())
+                .error_other(AnswerParseError::InvalidAnswer(c))),
+        })
+        .collect()
 }
 
-fn check_input(input: &str) -> usize {
-    parse_input(input)
+/// Groups are separated by blank lines, with one person per line.
+pub fn parse_input(input: &str) -> Result<Vec<Vec<HashSet<char>>>> {
+    Span::new(input)
+        .records()
+        .map(|group| {
+            group
+                .lines()
+                .map(|line| parse_person(line.trim()))
+                .collect()
+        })
+        .collect::<Result<_, _>>()
+        .with_snippet()
+}
+
+/// Sums the number of questions anyone in each group answered.
+pub fn check_input(input: &[Vec<HashSet<char>>]) -> usize {
+    input
         .iter()
         .map(|group_answered| {
             group_answered.iter().fold(
@@ -32,45 +79,54 @@ fn check_input(input: &str) -> usize {
         .sum()
 }
 
-fn check_input2(input: &str) -> usize {
-    parse_input(input)
+/// Sums the number of questions everyone in each group answered.
+pub fn check_input2(input: &[Vec<HashSet<char>>]) -> usize {
+    input
         .iter()
         .map(|group_answered| {
             let mut iter = group_answered.iter();
-            let first = iter.next().unwrap();
-            iter.fold(first.to_owned(), |common, person| {
-                common.intersection(person).cloned().collect()
-            })
+            iter.next()
+                .map(|first| {
+                    iter.fold(first.to_owned(), |common, person| {
+                        common.intersection(person).cloned().collect()
+                    })
+                })
+                .unwrap_or_default()
         })
         .map(|common| common.len())
         .sum()
 }
 
-fn parse_goup(group: &str) -> Vec<HashSet<char>> {
-    group
-        .split('\n')
-        .fold(Vec::new(), |mut all_answered, person_answered| {
-            all_answered.push(person_answered.chars().collect());
-            all_answered
-        })
-}
-
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
     #[test]
-    fn test_check_input() {
+    fn test_check_input() -> Result<()> {
         let input = include_str!("../data/day6_test.txt");
-        let answered_questions_count = check_input(input);
+        let answered_questions_count = check_input(&parse_input(input)?);
         assert_eq!(answered_questions_count, 11);
+
+        Ok(())
     }
 
     #[test]
-    fn test_check_input2() {
+    fn test_check_input2() -> Result<()> {
         let input = include_str!("../data/day6_test.txt");
-        let answered_questions_count = check_input2(input);
+        let answered_questions_count = check_input2(&parse_input(input)?);
         assert_eq!(answered_questions_count, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let err = parse_input("abc\n\na\nB\n").unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", err),
+            "invalid input on line 4, column 1: invalid answer: 'B', expected a-z"
+        );
     }
 }
@@ -1,59 +1,105 @@
-use std::collections::{HashMap, HashSet};
+//! Day 7: Handy Haversacks, follow the rules of which bags contain which.
+
+use crate::{parse, solution::Solution};
+use eyre::{eyre, Result, WrapErr};
+use std::{
+    collections::{HashMap, HashSet},
+    num::ParseIntError,
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// Counts the bags that can hold a shiny gold bag, and then the bags a shiny gold bag holds.
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<(String, HashMap<String, usize>)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
 
-pub fn run() {
-    let input = include_str!("../data/day7.txt");
-    let a = check_input(input).len();
-    let b = check_input2(input);
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(check_input(input).len())
+    }
 
-    println!("day 7: {}, {}", a, b);
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        check_input2(input)
+    }
 }
 
-fn parse_input(input: &str) -> Vec<(&str, HashMap<&str, usize>)> {
-    input
-        .trim()
-        .split('\n')
-        .map(|rule| {
-            let mut rule = rule.split(" bags contain ");
-            let container_color = rule.next().unwrap();
-            let can_contain = rule.next().unwrap();
-
-            let can_contain = if can_contain.starts_with("no") {
-                HashMap::new()
-            } else {
-                can_contain
-                    .split(", ")
-                    .map(|containee| {
-                        let mut containee = containee.splitn(2, ' ');
-                        let count: usize = containee.next().unwrap().parse().unwrap();
-                        let color = containee.next().unwrap().split(" bag").next().unwrap();
-
-                        (color, count)
-                    })
-                    .collect::<HashMap<_, _>>()
-            };
-
-            (container_color, can_contain)
-        })
-        .collect::<Vec<_>>()
+#[derive(Error, Debug)]
+pub enum RuleParseError {
+    #[error("invalid rule: '{0}', expected '<color> bags contain <contents>'")]
+    MissingContents(String),
+    #[error("invalid contents: '{0}', expected '<count> <color> bag(s)'")]
+    InvalidContents(String),
+    #[error("invalid count: '{0}'")]
+    InvalidCount(String, #[source] ParseIntError),
 }
 
-fn check_input(input: &str) -> HashSet<&str> {
-    let contains_by_color: HashMap<&str, HashSet<&str>> = parse_input(input).into_iter().fold(
-        HashMap::new(),
-        |mut map, (container_color, contains)| {
-            for (contained_color, _) in contains {
-                map.entry(contained_color)
-                    .or_default()
-                    .insert(container_color);
-            }
-            map
-        },
-    );
+fn parse_contents(contents: &str) -> Result<(String, usize), RuleParseError> {
+    let invalid_contents = || RuleParseError::InvalidContents(contents.to_string());
+
+    let (count, color) = contents.split_once(' ').ok_or_else(invalid_contents)?;
+    let count =
+        usize::from_str(count).map_err(|e| RuleParseError::InvalidCount(count.to_string(), e))?;
+    let (color, _) = color.split_once(" bag").ok_or_else(invalid_contents)?;
+
+    Ok((color.to_string(), count))
+}
+
+/// Parses a rule into the container color and the count of each color it holds.
+pub fn parse_rule(rule: &str) -> Result<(String, HashMap<String, usize>), RuleParseError> {
+    let (container_color, can_contain) = rule
+        .split_once(" bags contain ")
+        .ok_or_else(|| RuleParseError::MissingContents(rule.to_string()))?;
+
+    let can_contain = if can_contain.starts_with("no") {
+        HashMap::new()
+    } else {
+        can_contain
+            .split(", ")
+            .map(parse_contents)
+            .collect::<Result<HashMap<_, _>, _>>()?
+    };
+
+    Ok((container_color.to_string(), can_contain))
+}
+
+/// Parses one rule per line.
+pub fn parse_input(input: &str) -> Result<Vec<(String, HashMap<String, usize>)>> {
+    parse::numbered_lines(input)
+        .map(|(number, rule)| {
+            parse_rule(rule).wrap_err_with(|| format!("invalid input on line {}", number))
+        })
+        .collect()
+}
 
-    let mut outermost_bag_colors = contains_by_color.get("shiny gold").unwrap().to_owned();
+/// The colors of the bags that can eventually hold a shiny gold bag.
+pub fn check_input(input: &[(String, HashMap<String, usize>)]) -> HashSet<&str> {
+    let contains_by_color: HashMap<&str, HashSet<&str>> =
+        input
+            .iter()
+            .fold(HashMap::new(), |mut map, (container_color, contains)| {
+                for contained_color in contains.keys() {
+                    map.entry(contained_color.as_str())
+                        .or_default()
+                        .insert(container_color.as_str());
+                }
+                map
+            });
+
+    let mut outermost_bag_colors = contains_by_color
+        .get("shiny gold")
+        .cloned()
+        .unwrap_or_default();
     let mut checked_bag_colors = HashSet::new();
 
-    for _ in 0..1000 {
+    // the set of colors only grows and every color is checked once, so this terminates
+    loop {
         let unchecked_bag_colors = outermost_bag_colors
             .difference(&checked_bag_colors)
             .cloned()
@@ -71,18 +117,21 @@ fn check_input(input: &str) -> HashSet<&str> {
             return outermost_bag_colors;
         }
     }
-
-    panic!("infinite loop?!")
 }
 
-fn check_input2(input: &str) -> usize {
-    let mut contains_by_color = parse_input(input).into_iter().fold(
-        HashMap::new(),
-        |mut map, (container_color, contains)| {
-            map.insert(container_color, contains);
-            map
-        },
-    );
+/// The number of bags inside a shiny gold bag.
+pub fn check_input2(input: &[(String, HashMap<String, usize>)]) -> Result<usize> {
+    let mut contains_by_color =
+        input
+            .iter()
+            .fold(HashMap::new(), |mut map, (container_color, contains)| {
+                let contains = contains
+                    .iter()
+                    .map(|(color, count)| (color.as_str(), *count))
+                    .collect::<HashMap<_, _>>();
+                map.insert(container_color.as_str(), contains);
+                map
+            });
 
     let mut contains_count_by_color = HashMap::new();
 
@@ -91,10 +140,10 @@ fn check_input2(input: &str) -> usize {
         "shiny gold",
         &mut contains_by_color,
         &mut contains_count_by_color,
-    );
+    )?;
 
     // we dont count the shiny gold bag
-    count - 1
+    Ok(count - 1)
 }
 
 fn count_contains<'a>(
@@ -102,11 +151,14 @@ fn count_contains<'a>(
     color: &'a str,
     contains_by_color: &mut HashMap<&'a str, HashMap<&'a str, usize>>,
     total_contained_by_color: &mut HashMap<&'a str, usize>,
-) -> usize {
+) -> Result<usize> {
     let total_contained = match total_contained_by_color.get(color) {
         Some(total_contained) => *total_contained,
         None => {
-            let contains = contains_by_color.remove(color).unwrap();
+            // a color that is already removed but not counted yet contains itself
+            let contains = contains_by_color
+                .remove(color)
+                .ok_or_else(|| eyre!("no rule for {} bags, or the rules contain a cycle", color))?;
 
             let mut total_contained = 0;
             for (contained_color, contained_count) in contains.into_iter() {
@@ -115,7 +167,7 @@ fn count_contains<'a>(
                     contained_color,
                     contains_by_color,
                     total_contained_by_color,
-                );
+                )?;
             }
 
             total_contained_by_color.insert(color, total_contained);
@@ -124,7 +176,7 @@ fn count_contains<'a>(
         }
     };
 
-    count + (count * total_contained)
+    Ok(count + (count * total_contained))
 }
 
 #[cfg(test)]
@@ -133,23 +185,61 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_check_input() {
+    fn test_check_input() -> Result<()> {
         let input = include_str!("../data/day7_test.txt");
-        let container_bags = check_input(input);
+        let rules = parse_input(input)?;
+        let container_bags = check_input(&rules);
         assert_eq!(container_bags.len(), 4);
+
+        Ok(())
     }
 
     #[test]
-    fn test_check_input2() {
+    fn test_check_input2() -> Result<()> {
         let input = include_str!("../data/day7_test.txt");
-        let answered_questions_count = check_input2(input);
+        let answered_questions_count = check_input2(&parse_input(input)?)?;
         assert_eq!(answered_questions_count, 32);
+
+        Ok(())
     }
 
     #[test]
-    fn test_check_input2_2() {
+    fn test_check_input2_2() -> Result<()> {
         let input = include_str!("../data/day7_test_2.txt");
-        let answered_questions_count = check_input2(input);
+        let answered_questions_count = check_input2(&parse_input(input)?)?;
         assert_eq!(answered_questions_count, 126);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let tests = vec![
+            (
+                "light red bags contain 1 bright white bag.\ndark orange bags hold 3 bright white bags.",
+                "invalid input on line 2: invalid rule: 'dark orange bags hold 3 bright white bags.', expected '<color> bags contain <contents>'",
+            ),
+            (
+                "light red bags contain one bright white bag.",
+                "invalid input on line 1: invalid count: 'one': invalid digit found in string",
+            ),
+            (
+                "light red bags contain 1 bright white.",
+                "invalid input on line 1: invalid contents: '1 bright white.', expected '<count> <color> bag(s)'",
+            ),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(format!("{:#}", parse_input(input).unwrap_err()), want);
+        }
+    }
+
+    #[test]
+    fn test_check_input2_missing_rule() -> Result<()> {
+        let rules = parse_input("shiny gold bags contain 2 dark red bags.")?;
+
+        assert!(check_input2(&rules).is_err());
+
+        Ok(())
     }
 }
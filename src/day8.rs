@@ -1,53 +1,87 @@
-use std::collections::HashSet;
+//! Day 8: Handheld Halting, run the boot code of the handheld game console.
 
-pub fn run() {
-    let input = include_str!("../data/day8.txt");
-    let loop_detected_at = check_input(input);
-    let corrected_acc = check_input2(input).unwrap();
+use crate::{parse, solution::Solution};
+use eyre::{bail, eyre, Result, WrapErr};
+use std::{collections::HashSet, convert::TryFrom, num::ParseIntError, str::FromStr};
+use thiserror::Error;
 
-    println!("day 8: {} {}", loop_detected_at, corrected_acc);
+/// Finds the accumulator before the boot code loops, and then after fixing the one
+/// corrupted instruction that makes it loop.
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Vec<Op>;
+    type Answer1 = isize;
+    type Answer2 = isize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        check_input(input)
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        check_input2(input).ok_or_else(|| eyre!("no single correction terminates the program"))
+    }
 }
 
-#[derive(Debug, PartialEq)]
-enum Op {
+/// An instruction of the boot code.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
     Nop(isize),
     Acc(isize),
     Jmp(isize),
 }
 
+/// Runs the boot code one instruction at a time.
 #[derive(Debug)]
-struct Machine {
+pub struct Machine {
     acc: isize,
     pc: isize,
-    reverse: bool,
     ops: Vec<Op>,
     visited: HashSet<isize>,
-    tried: HashSet<isize>,
 }
 
 impl Machine {
-    fn new(ops: Vec<Op>) -> Self {
+    pub fn new(ops: Vec<Op>) -> Self {
         Self {
             acc: 0,
             pc: 0,
-            reverse: false,
             ops,
             visited: HashSet::new(),
-            tried: HashSet::new(),
         }
     }
 
-    fn run_until_loop_detected(&mut self) -> isize {
+    /// The accumulator.
+    pub fn acc(&self) -> isize {
+        self.acc
+    }
+
+    /// The index of the next instruction.
+    pub fn pc(&self) -> isize {
+        self.pc
+    }
+
+    /// Runs until an instruction is about to run a second time and returns the
+    /// accumulator, fails if the program jumps outside of itself.
+    pub fn run_until_loop_detected(&mut self) -> Result<isize> {
         loop {
             if !self.visited.insert(self.pc) {
-                return self.acc;
+                return Ok(self.acc);
             }
-            self.exec_next();
+            self.exec_next()?;
         }
     }
 
-    fn exec_next(&mut self) {
-        let op = self.ops.get(self.pc as usize).unwrap();
+    /// Runs the next instruction.
+    pub fn exec_next(&mut self) -> Result<()> {
+        let ops = &self.ops;
+        let op = match usize::try_from(self.pc).ok().and_then(|pc| ops.get(pc)) {
+            Some(op) => op,
+            None => bail!("pc {} is outside of the program, it has no loop", self.pc),
+        };
         // println!("pc: {}, op: {:?}", self.pc, &op);
         match op {
             Op::Nop(_) => {
@@ -59,34 +93,56 @@ impl Machine {
             }
             Op::Jmp(n) => self.pc += n,
         }
+
+        Ok(())
     }
 }
 
-fn parse_input(input: &str) -> Vec<Op> {
-    input
-        .trim()
-        .split('\n')
-        .map(|row| {
-            let mut row = row.split(' ');
-            let op = row.next().unwrap();
-            let n = row.next().unwrap().parse().unwrap();
-            match op {
-                "acc" => Op::Acc(n),
-                "jmp" => Op::Jmp(n),
-                "nop" => Op::Nop(n),
-                op => panic!("invalid op: {:?}", op),
-            }
+#[derive(Error, Debug)]
+pub enum OpParseError {
+    #[error("invalid instruction: '{0}', expected '<op> <argument>'")]
+    MissingArgument(String),
+    #[error("invalid op: '{0}'")]
+    InvalidOp(String),
+    #[error("invalid argument: '{0}'")]
+    InvalidArgument(String, #[source] ParseIntError),
+}
+
+impl FromStr for Op {
+    type Err = OpParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (op, n) = s
+            .split_once(' ')
+            .ok_or_else(|| OpParseError::MissingArgument(s.to_string()))?;
+        let n = isize::from_str(n).map_err(|e| OpParseError::InvalidArgument(n.to_string(), e))?;
+        match op {
+            "acc" => Ok(Op::Acc(n)),
+            "jmp" => Ok(Op::Jmp(n)),
+            "nop" => Ok(Op::Nop(n)),
+            _ => Err(OpParseError::InvalidOp(op.to_string())),
+        }
+    }
+}
+
+/// Parses one `<op> <argument>` instruction per line.
+pub fn parse_input(input: &str) -> Result<Vec<Op>> {
+    parse::numbered_lines(input)
+        .map(|(number, row)| {
+            Op::from_str(row).wrap_err_with(|| format!("invalid input on line {}", number))
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
-fn check_input(input: &str) -> isize {
-    let mut m = Machine::new(parse_input(input));
+/// The accumulator right before the program loops.
+pub fn check_input(ops: &[Op]) -> Result<isize> {
+    let mut m = Machine::new(ops.to_vec());
     m.run_until_loop_detected()
 }
 
-fn check_input2(input: &str) -> Option<isize> {
-    run_and_correct_recursive(&parse_input(input), &mut HashSet::new(), 0, 0, false)
+/// The accumulator when the program terminates after swapping a single `jmp` and `nop`.
+pub fn check_input2(ops: &[Op]) -> Option<isize> {
+    run_and_correct_recursive(ops, &mut HashSet::new(), 0, 0, false)
 }
 
 fn run_and_correct_recursive(
@@ -106,7 +162,8 @@ fn run_and_correct_recursive(
         return None;
     }
 
-    let op = ops.get(pc as usize).unwrap();
+    // a negative pc jumped to before the start of the program
+    let op = ops.get(usize::try_from(pc).ok()?)?;
 
     let (try_pc, try_acc) = match op {
         Op::Nop(_) => (pc + 1, acc),
@@ -146,9 +203,9 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_input() {
+    fn test_parse_input() -> Result<()> {
         let input = include_str!("../data/day8_test.txt");
-        let parsed = parse_input(input);
+        let parsed = parse_input(input)?;
 
         assert_eq!(
             parsed,
@@ -164,20 +221,56 @@ mod tests {
                 Op::Acc(6),
             ]
         );
+
+        Ok(())
     }
 
     #[test]
-    fn test_check_input() {
+    fn test_parse_invalid() {
+        let tests =
+            vec![
+            (
+                "nop +0\nacc+1",
+                "invalid input on line 2: invalid instruction: 'acc+1', expected '<op> <argument>'",
+            ),
+            ("nop +0\nadd +1", "invalid input on line 2: invalid op: 'add'"),
+            ("\n\nnop +0\nadd +1\n", "invalid input on line 4: invalid op: 'add'"),
+            (
+                "jmp x",
+                "invalid input on line 1: invalid argument: 'x': invalid digit found in string",
+            ),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(format!("{:#}", parse_input(input).unwrap_err()), want);
+        }
+    }
+
+    #[test]
+    fn test_check_input() -> Result<()> {
         let input = include_str!("../data/day8_test.txt");
-        let loop_detected_at = check_input(input);
+        let loop_detected_at = check_input(&parse_input(input)?)?;
         dbg!(loop_detected_at);
         assert_eq!(loop_detected_at, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_input_out_of_bounds() -> Result<()> {
+        assert!(check_input(&parse_input("nop +0\nacc +1")?).is_err());
+        assert!(check_input(&parse_input("jmp -1")?).is_err());
+        assert_eq!(check_input2(&parse_input("jmp -1\nacc +1")?), Some(1));
+
+        Ok(())
     }
 
     #[test]
-    fn test_check_input2() {
+    fn test_check_input2() -> Result<()> {
         let input = include_str!("../data/day8_test.txt");
-        let acc = check_input2(input);
+        let acc = check_input2(&parse_input(input)?);
         assert_eq!(acc, Some(8));
+
+        Ok(())
     }
 }
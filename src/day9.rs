@@ -1,35 +1,66 @@
-use std::str::FromStr;
-use tracing::{debug, info};
+//! Day 9: Encoding Error, break the XMAS encryption.
 
-pub fn run() {
-    let input = include_str!("../data/day9.txt");
-    let parsed = parse_input(input);
-    let first_invalid = find_first_invalid(&parsed, 25);
-    let (min, max) = find_range(&parsed, first_invalid);
-    let sum = min + max;
+use crate::{parse, solution::Solution};
+use eyre::{eyre, Result, WrapErr};
+use std::{num::ParseIntError, str::FromStr};
+use thiserror::Error;
+use tracing::debug;
 
-    info!(first_invalid, sum);
+/// Finds the first number that is not a sum of two of the previous 25, and then the
+/// weakness of the contiguous range that sums to it.
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        first_invalid(input)
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        let first_invalid = first_invalid(input)?;
+        let (min, max) = find_range(input, first_invalid)
+            .ok_or_else(|| eyre!("no contiguous range sums to {}", first_invalid))?;
+        Ok(min + max)
+    }
 }
 
-fn parse_input(input: &str) -> Vec<usize> {
-    input
-        .trim()
-        .lines()
-        .map(FromStr::from_str)
-        .collect::<Result<Vec<usize>, _>>()
-        .expect("invalid input")
+fn first_invalid(input: &[usize]) -> Result<usize> {
+    find_first_invalid(input, 25).ok_or_else(|| eyre!("every number is a sum of the previous 25"))
 }
 
-fn find_first_invalid(input: &[usize], preamble_length: usize) -> usize {
-    for i in preamble_length..input.len() {
-        let current = *input.get(i).unwrap();
+#[derive(Error, Debug)]
+pub enum NumberParseError {
+    #[error("invalid number: '{0}'")]
+    InvalidNumber(String, #[source] ParseIntError),
+}
+
+/// Parses one number per line.
+pub fn parse_input(input: &str) -> Result<Vec<usize>> {
+    parse::numbered_lines(input)
+        .map(|(number, line)| {
+            usize::from_str(line)
+                .map_err(|e| NumberParseError::InvalidNumber(line.to_string(), e))
+                .wrap_err_with(|| format!("invalid input on line {}", number))
+        })
+        .collect()
+}
+
+/// The first number that is not the sum of two of the `preamble_length` numbers before it.
+pub fn find_first_invalid(input: &[usize], preamble_length: usize) -> Option<usize> {
+    for (i, &current) in input.iter().enumerate().skip(preamble_length) {
         debug!(i, current);
         let mut found = false;
 
-        'search: for j in i - preamble_length..i - 1 {
-            let a = *input.get(j).unwrap();
-            for k in (i - preamble_length + 1)..i {
-                let b = *input.get(k).unwrap();
+        let preamble = input.iter().enumerate().take(i);
+        'search: for (j, &a) in preamble.clone().take(i - 1).skip(i - preamble_length) {
+            for (k, &b) in preamble.clone().skip(i - preamble_length + 1) {
                 let candidate = a + b;
                 debug!(j, k, candidate);
                 if candidate == current {
@@ -42,21 +73,21 @@ fn find_first_invalid(input: &[usize], preamble_length: usize) -> usize {
         debug!(found);
 
         if !found {
-            return current;
+            return Some(current);
         }
     }
 
-    panic!("invalid input!");
+    None
 }
 
-fn find_range(input: &[usize], target: usize) -> (usize, usize) {
-    'search: for i in 0..input.len() {
-        let mut candidate = *input.get(i).unwrap();
+/// The smallest and largest number of the first contiguous range, of at least two
+/// numbers, that sums to `target`.
+pub fn find_range(input: &[usize], target: usize) -> Option<(usize, usize)> {
+    'search: for (i, &first) in input.iter().enumerate() {
+        let mut candidate = first;
         let mut min = candidate;
         let mut max = candidate;
-        for j in i + 1..input.len() {
-            let current = *input.get(j).unwrap();
-
+        for &current in &input[i + 1..] {
             candidate += current;
             if candidate > target {
                 continue 'search;
@@ -66,12 +97,12 @@ fn find_range(input: &[usize], target: usize) -> (usize, usize) {
             max = max.max(current);
 
             if candidate == target {
-                return (min, max);
+                return Some((min, max));
             }
         }
     }
 
-    panic!("invalid input!");
+    None
 }
 
 #[cfg(test)]
@@ -82,7 +113,7 @@ mod tests {
     #[test]
     fn test_parse_input() -> Result<()> {
         let input = include_str!("../data/day9_test.txt");
-        let parsed = parse_input(input);
+        let parsed = parse_input(input)?;
         dbg!(parsed);
 
         Ok(())
@@ -91,23 +122,40 @@ mod tests {
     #[test]
     fn test_part_1() -> Result<()> {
         let input = include_str!("../data/day9_test.txt");
-        let parsed = parse_input(input);
+        let parsed = parse_input(input)?;
         let answer = find_first_invalid(&parsed, 5);
 
-        debug!(answer);
+        debug!(?answer);
 
-        assert_eq!(answer, 127);
+        assert_eq!(answer, Some(127));
         Ok(())
     }
 
     #[test]
     fn test_part_2() -> Result<()> {
         let input = include_str!("../data/day9_test.txt");
-        let parsed = parse_input(input);
-        let first_invalid = find_first_invalid(&parsed, 5);
-        let (min, max) = find_range(&parsed, first_invalid);
+        let parsed = parse_input(input)?;
+        let first_invalid = find_first_invalid(&parsed, 5).unwrap();
 
-        assert_eq!((min, max), (15, 47));
+        assert_eq!(find_range(&parsed, first_invalid), Some((15, 47)));
+        assert_eq!(find_range(&parsed, 1), None);
         Ok(())
     }
+
+    #[test]
+    fn test_parse_invalid() {
+        let err = parse_input("35\n20\n-15\n").unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", err),
+            "invalid input on line 3: invalid number: '-15': invalid digit found in string"
+        );
+
+        let err = parse_input("\n35\n20\n-15\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "invalid input on line 4: invalid number: '-15': invalid digit found in string",
+            "leading blank lines are counted"
+        );
+    }
 }
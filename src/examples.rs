@@ -0,0 +1,136 @@
+//! The example inputs in the data directory and their expected answers.
+
+use crate::{
+    answers::{DayAnswers, Verdict},
+    solution::Part,
+};
+use eyre::{eyre, Result, WrapErr};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// An example input in the data directory, `dayN_<name>.txt`, with the expected answers
+/// recorded next to it in `dayN_<name>.answers.toml`. Only the parts that have an
+/// expected answer are checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub day: u8,
+    pub path: PathBuf,
+    pub expected: DayAnswers,
+}
+
+/// The day of an example file name, `day14_test_2.txt` is an example for day 14.
+fn example_day(file_name: &str) -> Option<u8> {
+    let name = file_name.strip_prefix("day")?.strip_suffix(".txt")?;
+    let (day, _) = name.split_once('_')?;
+    day.parse().ok()
+}
+
+/// Finds every example in `data_dir` that has expected answers, sorted by day.
+pub fn discover(data_dir: &Path) -> Result<Vec<Example>> {
+    let mut examples = Vec::new();
+
+    let entries = fs::read_dir(data_dir)
+        .wrap_err_with(|| format!("failed to read examples from {}", data_dir.display()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+        let day = match path
+            .file_name()
+            .and_then(|name| example_day(&name.to_string_lossy()))
+        {
+            Some(day) => day,
+            None => continue,
+        };
+
+        let answers_path = path.with_extension("answers.toml");
+        if !answers_path.exists() {
+            continue;
+        }
+
+        let expected = fs::read_to_string(&answers_path)
+            .map_err(eyre::Report::new)
+            .and_then(|input| Ok(toml::from_str::<DayAnswers>(&input)?))
+            .wrap_err_with(|| format!("failed to read answers from {}", answers_path.display()))?;
+
+        examples.push(Example {
+            day,
+            path,
+            expected,
+        });
+    }
+
+    examples.sort_by(|a, b| (a.day, &a.path).cmp(&(b.day, &b.path)));
+
+    Ok(examples)
+}
+
+impl Example {
+    /// Solves the parts that have an expected answer and checks the answers.
+    pub fn check(&self) -> Result<Vec<(Part, Verdict)>> {
+        let solution =
+            crate::get_day(self.day).ok_or_else(|| eyre!("day {} does not exist", self.day))?;
+        let input = fs::read_to_string(&self.path)?;
+        let parsed = solution.parse_input(&input)?;
+
+        Part::all()
+            .iter()
+            .filter(|&&part| self.expected.get(part).is_some())
+            .map(|&part| {
+                let answer = solution.solve(&parsed, part)?;
+                Ok((part, self.expected.check(part, &answer)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_day() {
+        let tests = vec![
+            ("day14_test_2.txt", Some(14)),
+            ("day4_test.txt", Some(4)),
+            ("day4.txt", None),
+            ("day4_test.answers.toml", None),
+            ("dayx_test.txt", None),
+        ];
+
+        for (file_name, want) in tests {
+            assert_eq!(example_day(file_name), want, "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn test_examples() -> Result<()> {
+        let examples = discover(Path::new("data"))?;
+        assert!(!examples.is_empty(), "no examples found");
+
+        let mut failures = Vec::new();
+
+        for example in examples {
+            match example.check() {
+                Ok(verdicts) => {
+                    for (part, verdict) in verdicts {
+                        if verdict != Verdict::Match {
+                            failures.push(format!(
+                                "{} part {}: {:?}",
+                                example.path.display(),
+                                part,
+                                verdict
+                            ));
+                        }
+                    }
+                }
+                Err(e) => failures.push(format!("{}: {:#}", example.path.display(), e)),
+            }
+        }
+
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1052 @@
+//! Seeded random inputs for the days, for stress and property testing.
+//!
+//! Every generator produces an input the day's solution can parse and solve, the size
+//! counts lines, records or tiles depending on the day. The same day, size and seed always
+//! give the same input.
+
+use crate::grid::{Grid, Orientation};
+use eyre::{bail, eyre, Result};
+use rand::{
+    seq::{index, SliceRandom},
+    Rng, SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+use std::{collections::HashSet, fmt::Display, ops::RangeInclusive};
+
+/// The random number generator the inputs are made with, ChaCha gives the same numbers
+/// on every platform.
+pub type InputRng = ChaCha8Rng;
+
+/// Generates the inputs of one day.
+pub struct Generator {
+    pub day: u8,
+    /// What the size counts, e.g. `lines`
+    pub unit: &'static str,
+    pub sizes: RangeInclusive<usize>,
+    /// About the size of the puzzle input
+    pub default_size: usize,
+    generate: fn(&mut InputRng, usize) -> String,
+}
+
+impl Generator {
+    /// The input of `size` for `seed`.
+    pub fn generate(&self, size: usize, seed: u64) -> Result<String> {
+        if !self.sizes.contains(&size) {
+            bail!(
+                "invalid size for day {}: {}, expected {} to {} {}",
+                self.day,
+                size,
+                self.sizes.start(),
+                self.sizes.end(),
+                self.unit
+            );
+        }
+
+        let mut rng = InputRng::seed_from_u64(seed);
+        Ok((self.generate)(&mut rng, size))
+    }
+}
+
+static GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        unit: "expenses",
+        sizes: 5..=100_000,
+        default_size: 200,
+        generate: day1,
+    },
+    Generator {
+        day: 2,
+        unit: "passwords",
+        sizes: 1..=1_000_000,
+        default_size: 1000,
+        generate: day2,
+    },
+    Generator {
+        day: 3,
+        unit: "rows",
+        sizes: 1..=1_000_000,
+        default_size: 323,
+        generate: day3,
+    },
+    Generator {
+        day: 4,
+        unit: "passports",
+        sizes: 1..=1_000_000,
+        default_size: 290,
+        generate: day4,
+    },
+    Generator {
+        day: 5,
+        unit: "rows of seats",
+        sizes: 3..=125,
+        default_size: 110,
+        generate: day5,
+    },
+    Generator {
+        day: 6,
+        unit: "groups",
+        sizes: 1..=1_000_000,
+        default_size: 490,
+        generate: day6,
+    },
+    Generator {
+        day: 7,
+        unit: "bag colors",
+        sizes: 2..=ADJECTIVES.len() * COLORS.len(),
+        default_size: 590,
+        generate: day7,
+    },
+    Generator {
+        day: 8,
+        unit: "instructions",
+        sizes: 2..=2000,
+        default_size: 630,
+        generate: day8,
+    },
+    Generator {
+        day: 9,
+        unit: "numbers",
+        sizes: 28..=1000,
+        default_size: 1000,
+        generate: day9,
+    },
+    Generator {
+        day: 10,
+        unit: "adapters",
+        sizes: 1..=1_000_000,
+        default_size: 100,
+        generate: day10,
+    },
+    Generator {
+        day: 11,
+        unit: "rows and columns",
+        sizes: 1..=500,
+        default_size: 95,
+        generate: day11,
+    },
+    Generator {
+        day: 12,
+        unit: "instructions",
+        sizes: 1..=1_000_000,
+        default_size: 780,
+        generate: day12,
+    },
+    Generator {
+        day: 13,
+        unit: "bus slots",
+        sizes: 1..=1000,
+        default_size: 60,
+        generate: day13,
+    },
+    Generator {
+        day: 14,
+        unit: "lines",
+        sizes: 1..=100_000,
+        default_size: 570,
+        generate: day14,
+    },
+    Generator {
+        day: 15,
+        unit: "starting numbers",
+        sizes: 1..=100,
+        default_size: 6,
+        generate: day15,
+    },
+    Generator {
+        day: 16,
+        unit: "nearby tickets",
+        sizes: 1..=100_000,
+        default_size: 240,
+        generate: day16,
+    },
+    Generator {
+        day: 17,
+        unit: "rows and columns",
+        sizes: 1..=32,
+        default_size: 8,
+        generate: day17,
+    },
+    Generator {
+        day: 18,
+        unit: "expressions",
+        sizes: 1..=1_000_000,
+        default_size: 370,
+        generate: day18,
+    },
+    Generator {
+        day: 19,
+        unit: "messages",
+        sizes: 1..=100_000,
+        default_size: 450,
+        generate: day19,
+    },
+    Generator {
+        day: 20,
+        unit: "tiles per side",
+        sizes: 2..=12,
+        default_size: 12,
+        generate: day20,
+    },
+];
+
+/// The generator for `day`, if there is one.
+pub fn get_generator(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.day == day)
+}
+
+/// The input for `day` of `size`, or the size of the puzzle input, for `seed`.
+pub fn generate(day: u8, size: Option<usize>, seed: u64) -> Result<String> {
+    let generator = get_generator(day).ok_or_else(|| eyre!("no generator for day {}", day))?;
+    generator.generate(size.unwrap_or(generator.default_size), seed)
+}
+
+/// One item per line, with a trailing newline.
+fn lines<T: Display>(items: impl IntoIterator<Item = T>) -> String {
+    items
+        .into_iter()
+        .map(|item| format!("{}\n", item))
+        .collect()
+}
+
+fn random_letter(rng: &mut InputRng) -> char {
+    rng.gen_range(b'a'..=b'z') as char
+}
+
+/// Exactly one pair and one triple sum to 2020, the other expenses are too large to.
+fn day1(rng: &mut InputRng, size: usize) -> String {
+    let mut expenses: Vec<u64> = (0..size - 5).map(|_| rng.gen_range(1011..=2000)).collect();
+
+    let a = rng.gen_range(1..=4);
+    let (b, c) = (rng.gen_range(5..=9), rng.gen_range(5..=9));
+    expenses.extend_from_slice(&[a, 2020 - a, b, c, 2020 - b - c]);
+
+    expenses.shuffle(rng);
+    lines(expenses)
+}
+
+fn day2(rng: &mut InputRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let letter = random_letter(rng);
+        let min = rng.gen_range(1..=5);
+        let max = rng.gen_range(min + 1..=min + 10);
+
+        let password: String = (0..rng.gen_range(min..=max + 4))
+            .map(|_| {
+                if rng.gen_bool(0.4) {
+                    letter
+                } else {
+                    random_letter(rng)
+                }
+            })
+            .collect();
+
+        format!("{}-{} {}: {}", min, max, letter, password)
+    }))
+}
+
+fn day3(rng: &mut InputRng, size: usize) -> String {
+    let map = Grid::from_fn(31, size, |_, _| if rng.gen_bool(0.25) { '#' } else { '.' });
+    format!("{}\n", map)
+}
+
+const EYE_COLORS: &[&str] = &["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+/// A value in `range`, or just outside of it when it should be invalid.
+fn year(rng: &mut InputRng, range: RangeInclusive<usize>, valid: bool) -> String {
+    let year = if valid {
+        rng.gen_range(range)
+    } else if rng.gen() {
+        range.start() - rng.gen_range(1..=20)
+    } else {
+        range.end() + rng.gen_range(1..=20)
+    };
+
+    year.to_string()
+}
+
+fn passport_field(rng: &mut InputRng, key: &str, valid: bool) -> String {
+    match key {
+        "byr" => year(rng, 1920..=2002, valid),
+        "iyr" => year(rng, 2010..=2020, valid),
+        "eyr" => year(rng, 2020..=2030, valid),
+        "hgt" => match (valid, rng.gen()) {
+            (true, true) => format!("{}cm", rng.gen_range(150..=193)),
+            (true, false) => format!("{}in", rng.gen_range(59..=76)),
+            (false, true) => format!("{}cm", rng.gen_range(194..=250)),
+            (false, false) => rng.gen_range(50..=200).to_string(),
+        },
+        "hcl" => {
+            let hex = format!("{:06x}", rng.gen_range(0..0x100_0000));
+            if valid {
+                format!("#{}", hex)
+            } else {
+                hex
+            }
+        }
+        "ecl" if valid => EYE_COLORS.choose(rng).unwrap().to_string(),
+        "ecl" => ["zzz", "gmt", "xry", "lzr"]
+            .choose(rng)
+            .unwrap()
+            .to_string(),
+        "pid" => {
+            let digits = if valid { 9 } else { rng.gen_range(7..=8) };
+            (0..digits)
+                .map(|_| rng.gen_range(0..10).to_string())
+                .collect()
+        }
+        _ => rng.gen_range(100..=350).to_string(),
+    }
+}
+
+/// Most passports have every field, some of them with an invalid value.
+fn day4(rng: &mut InputRng, size: usize) -> String {
+    let passports: Vec<String> = (0..size)
+        .map(|_| {
+            let mut fields = Vec::new();
+            for &key in ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"].iter() {
+                if rng.gen_bool(if key == "cid" { 0.5 } else { 0.9 }) {
+                    let valid = rng.gen_bool(0.9);
+                    fields.push(format!("{}:{}", key, passport_field(rng, key, valid)));
+                }
+            }
+            fields.shuffle(rng);
+
+            fields
+                .iter()
+                .map(|field| {
+                    let separator = if rng.gen_bool(0.3) { '\n' } else { ' ' };
+                    format!("{}{}", field, separator)
+                })
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    format!("{}\n", passports.join("\n\n"))
+}
+
+/// Full rows of seats apart from a single free seat in one of the middle rows.
+fn day5(rng: &mut InputRng, size: usize) -> String {
+    let first_row = rng.gen_range(1..=127 - size);
+    let rows = first_row..first_row + size;
+    let free_seat = (
+        rng.gen_range(first_row + 1..rows.end - 1),
+        rng.gen_range(0..8),
+    );
+
+    let mut passes: Vec<String> = rows
+        .flat_map(|row| (0..8).map(move |seat| (row, seat)))
+        .filter(|&seat| seat != free_seat)
+        .map(|(row, seat)| {
+            let row = format!("{:07b}", row).replace('0', "F").replace('1', "B");
+            let seat = format!("{:03b}", seat).replace('0', "L").replace('1', "R");
+            row + &seat
+        })
+        .collect();
+
+    passes.shuffle(rng);
+    lines(passes)
+}
+
+/// The people in a group share some of their answers.
+fn day6(rng: &mut InputRng, size: usize) -> String {
+    let groups: Vec<String> = (0..size)
+        .map(|_| {
+            let common: HashSet<char> = (0..rng.gen_range(0..=8))
+                .map(|_| random_letter(rng))
+                .collect();
+
+            let people: Vec<String> = (0..rng.gen_range(1..=5))
+                .map(|_| {
+                    let mut answers: Vec<char> = (b'a'..=b'z')
+                        .map(char::from)
+                        .filter(|c| rng.gen_bool(if common.contains(c) { 0.8 } else { 0.1 }))
+                        .collect();
+                    if answers.is_empty() {
+                        answers.push(random_letter(rng));
+                    }
+                    answers.shuffle(rng);
+                    answers.into_iter().collect()
+                })
+                .collect();
+
+            people.join("\n")
+        })
+        .collect();
+
+    format!("{}\n", groups.join("\n\n"))
+}
+
+const ADJECTIVES: &[&str] = &[
+    "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+    "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy",
+];
+
+const COLORS: &[&str] = &[
+    "aqua",
+    "beige",
+    "black",
+    "blue",
+    "bronze",
+    "brown",
+    "chartreuse",
+    "coral",
+    "crimson",
+    "cyan",
+    "fuchsia",
+    "gold",
+    "gray",
+    "green",
+    "indigo",
+    "lavender",
+    "lime",
+    "magenta",
+    "maroon",
+    "olive",
+    "orange",
+    "plum",
+    "purple",
+    "red",
+    "salmon",
+    "silver",
+    "tan",
+    "teal",
+    "tomato",
+    "turquoise",
+    "violet",
+    "white",
+    "yellow",
+];
+
+/// The bags are in layers that only hold bags of the next layer, so the number of bags
+/// inside shiny gold stays small, shiny gold is in the middle layer.
+fn day7(rng: &mut InputRng, size: usize) -> String {
+    let mut colors: Vec<String> = ADJECTIVES
+        .iter()
+        .flat_map(|adjective| {
+            COLORS
+                .iter()
+                .map(move |color| format!("{} {}", adjective, color))
+        })
+        .filter(|color| color != "shiny gold")
+        .collect();
+    colors.shuffle(rng);
+    colors.truncate(size - 1);
+    colors.insert(0, "shiny gold".to_string());
+
+    let last_layer = (size - 1).min(6);
+    let layer = |i: usize| {
+        if i == 0 {
+            last_layer / 2
+        } else {
+            i % (last_layer + 1)
+        }
+    };
+
+    let mut rules: Vec<String> = colors
+        .iter()
+        .enumerate()
+        .map(|(i, color)| {
+            let next_layer: Vec<&String> = colors
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| layer(j) == layer(i) + 1)
+                .map(|(_, color)| color)
+                .collect();
+
+            let amount = rng.gen_range(0..=4).min(next_layer.len());
+            let contents: Vec<String> = next_layer
+                .choose_multiple(rng, amount)
+                .map(|inner| {
+                    let count = rng.gen_range(1..=5);
+                    let bags = if count == 1 { "bag" } else { "bags" };
+                    format!("{} {} {}", count, inner, bags)
+                })
+                .collect();
+
+            if contents.is_empty() {
+                format!("{} bags contain no other bags.", color)
+            } else {
+                format!("{} bags contain {}.", color, contents.join(", "))
+            }
+        })
+        .collect();
+
+    rules.shuffle(rng);
+    lines(rules)
+}
+
+/// A program that runs forward to the end, apart from one `jmp` back to an instruction
+/// it already ran, turning that `jmp` into a `nop` fixes it.
+fn day8(rng: &mut InputRng, size: usize) -> String {
+    let mut ops: Vec<(&str, isize)> = (0..size)
+        .map(|i| match rng.gen_range(0..3) {
+            0 => ("acc", rng.gen_range(-50..=50)),
+            1 => ("nop", rng.gen_range(-(size as isize)..=size as isize)),
+            _ => ("jmp", rng.gen_range(1..=(size - i).min(5)) as isize),
+        })
+        .collect();
+
+    let mut path = vec![0];
+    while let Some(&(op, n)) = ops.get(*path.last().unwrap()) {
+        let step = if op == "jmp" { n as usize } else { 1 };
+        path.push(path.last().unwrap() + step);
+    }
+    path.pop(); // the end of the program
+
+    if path.len() < 2 {
+        // the first instruction jumps to the end, start with a nop instead
+        ops[0] = ("nop", 0);
+        path = vec![0, 1];
+    }
+
+    let from = rng.gen_range(1..path.len());
+    let to = path[rng.gen_range(0..from)];
+    ops[path[from]] = ("jmp", to as isize - path[from] as isize);
+
+    lines(ops.iter().map(|(op, n)| format!("{} {:+}", op, n)))
+}
+
+/// Every number is a sum of two of the 25 before it, apart from one that is the sum of a
+/// contiguous range before it.
+fn day9(rng: &mut InputRng, size: usize) -> String {
+    const PREAMBLE: usize = 25;
+
+    let is_pair_sum = |window: &[usize], n: usize| {
+        window
+            .iter()
+            .enumerate()
+            .any(|(i, a)| window[i + 1..].iter().any(|b| a + b == n))
+    };
+
+    let mut numbers: Vec<usize> = (0..PREAMBLE).map(|_| rng.gen_range(1..=50)).collect();
+    let invalid_at = rng.gen_range(PREAMBLE + 2..size);
+
+    for i in PREAMBLE..size {
+        let window = &numbers[i - PREAMBLE..i];
+
+        let next = if i == invalid_at {
+            loop {
+                let start = rng.gen_range(0..i - 1);
+                let len = rng.gen_range(2..=(i - start).min(17));
+                let sum = numbers[start..start + len].iter().sum();
+                if !is_pair_sum(window, sum) {
+                    break sum;
+                }
+            }
+        } else {
+            // adding the smaller numbers keeps them from growing too fast
+            let mut smallest: Vec<usize> = window.to_vec();
+            smallest.sort_unstable();
+            let pair = index::sample(rng, 6, 2);
+            smallest[pair.index(0)] + smallest[pair.index(1)]
+        };
+
+        numbers.push(next);
+    }
+
+    lines(numbers)
+}
+
+/// Runs of one jolt differences between three jolt ones, as long as the number of
+/// arrangements fits in 50 bits.
+fn day10(rng: &mut InputRng, size: usize) -> String {
+    // the number of arrangements of a run of n one jolt differences
+    const RUN_ARRANGEMENTS: [usize; 5] = [1, 1, 2, 4, 7];
+
+    let mut adapters = Vec::with_capacity(size);
+    let mut joltage = 0;
+    let mut arrangements = 1usize;
+
+    while adapters.len() < size {
+        let mut run = rng.gen_range(0..RUN_ARRANGEMENTS.len());
+        while arrangements * RUN_ARRANGEMENTS[run] >= 1 << 50 {
+            run -= 1;
+        }
+        arrangements *= RUN_ARRANGEMENTS[run];
+
+        for _ in 0..run {
+            joltage += 1;
+            adapters.push(joltage);
+        }
+        joltage += 3;
+        adapters.push(joltage);
+    }
+
+    adapters.truncate(size);
+    adapters.shuffle(rng);
+    lines(adapters)
+}
+
+fn day11(rng: &mut InputRng, size: usize) -> String {
+    let layout = Grid::from_fn(
+        size,
+        size,
+        |_, _| if rng.gen_bool(0.75) { 'L' } else { '.' },
+    );
+    format!("{}\n", layout)
+}
+
+fn day12(rng: &mut InputRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let action = *b"NESWLRF".choose(rng).unwrap() as char;
+        let value = match action {
+            'L' | 'R' => 90 * rng.gen_range(1..=3),
+            _ => rng.gen_range(1..=100),
+        };
+
+        format!("{}{}", action, value)
+    }))
+}
+
+/// Primes, so the bus ids are pairwise coprime, small enough that the product of nine of
+/// them fits.
+const BUS_IDS: &[usize] = &[
+    5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Up to nine buses, the first of them in the first slot.
+fn day13(rng: &mut InputRng, size: usize) -> String {
+    let buses = size.min(9);
+    let ids: Vec<&usize> = BUS_IDS.choose_multiple(rng, buses).collect();
+
+    let mut slots = vec!["x".to_string(); size];
+    slots[0] = ids[0].to_string();
+    for (&id, slot) in ids[1..].iter().zip(index::sample(rng, size - 1, buses - 1)) {
+        slots[slot + 1] = id.to_string();
+    }
+
+    format!(
+        "{}\n{}\n",
+        rng.gen_range(100_000..=1_000_000),
+        slots.join(",")
+    )
+}
+
+/// Masks with at most nine floating bits, so each write is to at most 512 addresses.
+fn day14(rng: &mut InputRng, size: usize) -> String {
+    let mask = |rng: &mut InputRng| {
+        let mut bits: Vec<char> = (0..36).map(|_| if rng.gen() { '1' } else { '0' }).collect();
+        let floating = rng.gen_range(0..=9);
+        for i in index::sample(rng, 36, floating) {
+            bits[i] = 'X';
+        }
+        bits.into_iter().collect::<String>()
+    };
+
+    let mut program = Vec::with_capacity(size);
+    while program.len() < size {
+        program.push(format!("mask = {}", mask(rng)));
+        for _ in 0..rng.gen_range(1..=6) {
+            program.push(format!(
+                "mem[{}] = {}",
+                rng.gen_range(0..1 << 16),
+                rng.gen_range(0..1 << 30)
+            ));
+        }
+    }
+
+    program.truncate(size);
+    lines(program)
+}
+
+/// Distinct starting numbers.
+fn day15(rng: &mut InputRng, size: usize) -> String {
+    let numbers: Vec<String> = index::sample(rng, 3 * size, size)
+        .into_iter()
+        .map(|n| n.to_string())
+        .collect();
+
+    format!("{}\n", numbers.join(","))
+}
+
+const TICKET_FIELDS: &[&str] = &[
+    "departure location",
+    "departure station",
+    "departure platform",
+    "departure track",
+    "departure date",
+    "departure time",
+    "arrival location",
+    "arrival station",
+    "arrival platform",
+    "arrival track",
+    "class",
+    "duration",
+    "price",
+    "route",
+    "row",
+    "seat",
+    "train",
+    "type",
+    "wagon",
+    "zone",
+];
+
+/// The range of field `i` covers the ranges of every field before it, so the column of
+/// the last field can only be that field, after that the column of the one before it and
+/// so on. The values of field `i` are between `50 * i` and `50 * i + 45`, so the product
+/// of six of them fits.
+fn day16(rng: &mut InputRng, size: usize) -> String {
+    let mut names = TICKET_FIELDS.to_vec();
+    names.shuffle(rng);
+
+    let mut columns: Vec<usize> = (0..TICKET_FIELDS.len()).collect();
+    columns.shuffle(rng);
+
+    let mut rules: Vec<String> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            format!(
+                "{}: 1-{} or {}-{}",
+                name,
+                50 * i + 20,
+                50 * i + 26,
+                50 * i + 45
+            )
+        })
+        .collect();
+    rules.shuffle(rng);
+
+    let ticket = |rng: &mut InputRng| {
+        columns
+            .iter()
+            .map(|&i| {
+                let value = if rng.gen() {
+                    rng.gen_range(50 * i + 1..=50 * i + 20)
+                } else {
+                    rng.gen_range(50 * i + 26..=50 * i + 45)
+                };
+                value.to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let my_ticket = ticket(rng).join(",");
+    let nearby_tickets: Vec<String> = (0..size)
+        .map(|i| {
+            let mut values = ticket(rng);
+            // keep the first ticket valid so every column has a value
+            if i > 0 && rng.gen_bool(0.25) {
+                let column = rng.gen_range(0..values.len());
+                values[column] = rng.gen_range(996..=999).to_string();
+            }
+            values.join(",")
+        })
+        .collect();
+
+    format!(
+        "{}\n\nyour ticket:\n{}\n\nnearby tickets:\n{}",
+        rules.join("\n"),
+        my_ticket,
+        lines(nearby_tickets)
+    )
+}
+
+fn day17(rng: &mut InputRng, size: usize) -> String {
+    let slice = Grid::from_fn(size, size, |_, _| if rng.gen_bool(0.4) { '#' } else { '.' });
+    format!("{}\n", slice)
+}
+
+/// An expression of `digits` single digit numbers, a term of more than one number is in
+/// parentheses. The value can not be larger than 9 to the power of `digits`.
+fn expression(rng: &mut InputRng, digits: usize) -> String {
+    let mut terms = vec![1; rng.gen_range(2..=digits.min(4))];
+    for _ in terms.len()..digits {
+        *terms.choose_mut(rng).unwrap() += 1;
+    }
+
+    let mut text = String::new();
+    for (i, digits) in terms.into_iter().enumerate() {
+        if i > 0 {
+            text.push_str(if rng.gen() { " + " } else { " * " });
+        }
+
+        if digits == 1 {
+            text.push_str(&rng.gen_range(1..=9).to_string());
+        } else {
+            text.push_str(&format!("({})", expression(rng, digits)));
+        }
+    }
+
+    text
+}
+
+fn day18(rng: &mut InputRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let digits = rng.gen_range(2..=12);
+        expression(rng, digits)
+    }))
+}
+
+/// A rule of the generated grammar.
+enum GrammarRule {
+    Char(char),
+    Either([usize; 2], [usize; 2]),
+}
+
+fn expand(rules: &[(usize, GrammarRule)], id: usize, rng: &mut InputRng, msg: &mut String) {
+    let (_, rule) = rules.iter().find(|(i, _)| *i == id).expect("rule exists");
+    match rule {
+        GrammarRule::Char(c) => msg.push(*c),
+        GrammarRule::Either(a, b) => {
+            for &id in if rng.gen() { a } else { b } {
+                expand(rules, id, rng, msg);
+            }
+        }
+    }
+}
+
+/// Rules 0, 8 and 11 are the ones from the puzzle. Every other rule is `a X | b Y`, the
+/// first character picks the alternative so the matcher never has to backtrack within
+/// rule 42 or 31. The messages match 42 42 31, or only the looping rules of part 2, or
+/// are random.
+fn day19(rng: &mut InputRng, size: usize) -> String {
+    let mut ids: Vec<usize> = (1..=140)
+        .filter(|id| ![8, 11, 31, 42].contains(id))
+        .collect();
+    ids.shuffle(rng);
+    let mut ids = ids.into_iter();
+
+    let (a, b) = (ids.next().unwrap(), ids.next().unwrap());
+    let mut rules = vec![(a, GrammarRule::Char('a')), (b, GrammarRule::Char('b'))];
+
+    let depth = rng.gen_range(3..=4);
+    let mut layer = vec![a, b];
+    for level in 1..=depth {
+        let top = level == depth;
+        let layer_ids: Vec<usize> = if top {
+            vec![42, 31]
+        } else {
+            (0..3).map(|_| ids.next().unwrap()).collect()
+        };
+
+        for &id in &layer_ids {
+            let rule = GrammarRule::Either(
+                [a, *layer.choose(rng).unwrap()],
+                [b, *layer.choose(rng).unwrap()],
+            );
+            rules.push((id, rule));
+        }
+        layer = layer_ids;
+    }
+
+    let mut rule_lines: Vec<String> = rules
+        .iter()
+        .map(|(id, rule)| match rule {
+            GrammarRule::Char(c) => format!("{}: \"{}\"", id, c),
+            GrammarRule::Either(x, y) => {
+                format!("{}: {} {} | {} {}", id, x[0], x[1], y[0], y[1])
+            }
+        })
+        .chain(vec![
+            "0: 8 11".to_string(),
+            "8: 42".to_string(),
+            "11: 42 31".to_string(),
+        ])
+        .collect();
+    rule_lines.shuffle(rng);
+
+    let messages: Vec<String> = (0..size)
+        .map(|_| {
+            let (n_42, n_31) = match rng.gen_range(0..3) {
+                0 => (2, 1),
+                1 => {
+                    let n_31 = rng.gen_range(1..=3);
+                    (rng.gen_range(n_31 + 1..=5), n_31)
+                }
+                _ => {
+                    let len = rng.gen_range(2..=5) * (depth + 1);
+                    return (0..len)
+                        .map(|_| if rng.gen() { 'a' } else { 'b' })
+                        .collect();
+                }
+            };
+
+            let mut msg = String::new();
+            for _ in 0..n_42 {
+                expand(&rules, 42, rng, &mut msg);
+            }
+            for _ in 0..n_31 {
+                expand(&rules, 31, rng, &mut msg);
+            }
+            msg
+        })
+        .collect();
+
+    format!("{}\n\n{}", rule_lines.join("\n"), lines(messages))
+}
+
+/// Sea monsters, the same as day 20 looks for.
+const MONSTER: &[&str] = &[
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+/// An image of `size` by `size` tiles cut into tiles that share their edges with their
+/// neighbours. No two edges have the same pattern, in either direction, so each edge only
+/// matches the tile next to it. The sea has a few monsters and every tile is rotated or
+/// flipped.
+fn day20(rng: &mut InputRng, size: usize) -> String {
+    let side = 9 * size + 1;
+    let mut image = Grid::from_fn(side, side, |x, y| {
+        if x % 9 == 0 && y % 9 == 0 {
+            rng.gen() // the corners of the tiles
+        } else {
+            rng.gen_bool(0.25)
+        }
+    });
+
+    let mut patterns = HashSet::new();
+    let mut edges = Vec::new();
+    for i in 0..=size {
+        for j in 0..size {
+            edges.push((9 * j, 9 * i, 1, 0)); // horizontal
+            edges.push((9 * i, 9 * j, 0, 1)); // vertical
+        }
+    }
+
+    for (x, y, dx, dy) in edges {
+        let corners = (image[(x, y)], image[(x + 9 * dx, y + 9 * dy)]);
+
+        let mut middles: Vec<u16> = (0..256).collect();
+        middles.shuffle(rng);
+        let pattern = middles
+            .into_iter()
+            .map(|middle| {
+                (0..10)
+                    .map(|i| match i {
+                        0 => corners.0,
+                        9 => corners.1,
+                        _ => middle & (1 << (i - 1)) != 0,
+                    })
+                    .collect::<Vec<bool>>()
+            })
+            .find(|pattern| {
+                let reversed: Vec<bool> = pattern.iter().rev().cloned().collect();
+                *pattern != reversed && !patterns.contains(pattern) && !patterns.contains(&reversed)
+            })
+            .expect("there are enough edge patterns for 12 by 12 tiles");
+
+        for (i, &cell) in pattern.iter().enumerate() {
+            image[(x + i * dx, y + i * dy)] = cell;
+        }
+        patterns.insert(pattern);
+    }
+
+    // the monsters go in the image without the edges of the tiles
+    let sea_side = 8 * size;
+    let mut monsters: Vec<(usize, usize)> = Vec::new();
+    if sea_side >= MONSTER[0].len() {
+        for _ in 0..size * size {
+            let (x, y) = (
+                rng.gen_range(0..=sea_side - MONSTER[0].len()),
+                rng.gen_range(0..=sea_side - MONSTER.len()),
+            );
+            let overlaps = monsters.iter().any(|&(mx, my)| {
+                (mx as isize - x as isize).abs() < MONSTER[0].len() as isize
+                    && (my as isize - y as isize).abs() < MONSTER.len() as isize
+            });
+            if !overlaps {
+                monsters.push((x, y));
+            }
+        }
+    }
+
+    for (x, y) in monsters {
+        for (dy, row) in MONSTER.iter().enumerate() {
+            for (dx, c) in row.chars().enumerate() {
+                if c == '#' {
+                    let (sx, sy) = (x + dx, y + dy);
+                    image[(9 * (sx / 8) + 1 + sx % 8, 9 * (sy / 8) + 1 + sy % 8)] = true;
+                }
+            }
+        }
+    }
+
+    let ids = index::sample(rng, 9000, size * size);
+    let mut tiles: Vec<String> = ids
+        .into_iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let (x, y) = (9 * (i % size), 9 * (i / size));
+            let tile = Grid::from_fn(
+                10,
+                10,
+                |dx, dy| {
+                    if image[(x + dx, y + dy)] {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                },
+            );
+            let orientation = Orientation::new(rng.gen_range(0..4), rng.gen());
+
+            format!("Tile {}:\n{}\n", id + 1000, tile.view(orientation))
+        })
+        .collect();
+
+    tiles.shuffle(rng);
+    tiles.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_day;
+
+    #[test]
+    fn test_generate_is_deterministic() -> Result<()> {
+        for generator in GENERATORS {
+            let size = *generator.sizes.start().max(&10).min(generator.sizes.end());
+
+            let input = generator.generate(size, 7)?;
+            assert_eq!(input, generator.generate(size, 7)?, "day {}", generator.day);
+            assert_ne!(input, generator.generate(size, 8)?, "day {}", generator.day);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() -> Result<()> {
+        for generator in GENERATORS {
+            let solution = get_day(generator.day).expect("every generator has a day");
+            let sizes = [
+                *generator.sizes.start(),
+                *generator.sizes.start().max(&10).min(generator.sizes.end()),
+            ];
+
+            for &size in sizes.iter() {
+                for seed in 0..3 {
+                    let input = generator.generate(size, seed)?;
+                    let parsed = solution.parse_input(&input)?;
+                    solution.solve(&parsed, crate::Part::One)?;
+                    // 30 million turns, and four dimensions, take too long in a debug build
+                    if generator.day != 15 && generator.day != 17 {
+                        solution.solve(&parsed, crate::Part::Two)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_invalid() {
+        assert_eq!(
+            format!("{:#}", generate(20, Some(13), 1).unwrap_err()),
+            "invalid size for day 20: 13, expected 2 to 12 tiles per side"
+        );
+        assert_eq!(
+            format!("{:#}", generate(25, None, 1).unwrap_err()),
+            "no generator for day 25"
+        );
+    }
+}
@@ -0,0 +1,558 @@
+//! A rectangular grid of cells parsed from text, shared by the days that work on maps.
+
+use crate::parse;
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+use thiserror::Error;
+
+/// The offsets of the four orthogonal neighbours, clockwise from up.
+pub const DIRECTIONS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The offsets of all eight neighbours, clockwise from up.
+pub const DIRECTIONS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+#[derive(Error, Debug, PartialEq)]
+pub enum GridParseError {
+    #[error("empty grid")]
+    Empty,
+    #[error("invalid cell on line {line}, column {column}: '{cell}'")]
+    InvalidCell {
+        line: usize,
+        column: usize,
+        cell: char,
+    },
+    #[error("invalid row width on line {line}: {got}, expected {want}")]
+    InvalidWidth {
+        line: usize,
+        want: usize,
+        got: usize,
+    },
+}
+
+/// A `width` by `height` grid stored row by row, `x` is the column and `y` the row
+/// counted from the top left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// A grid with the cells set to `f(x, y)`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parses one row per line, `cell` maps each character to a cell or `None` if the
+    /// character is invalid. All rows must have the same width, blank lines at the start
+    /// and end are skipped.
+    pub fn parse(
+        input: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, GridParseError> {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+
+        for (line_number, line) in parse::numbered_lines(input) {
+            let row_start = cells.len();
+
+            for (x, c) in line.chars().enumerate() {
+                let value = cell(c).ok_or(GridParseError::InvalidCell {
+                    line: line_number,
+                    column: x + 1,
+                    cell: c,
+                })?;
+                cells.push(value);
+            }
+
+            let got = cells.len() - row_start;
+            if height == 0 {
+                width = got;
+            } else if got != width {
+                return Err(GridParseError::InvalidWidth {
+                    line: line_number,
+                    want: width,
+                    got,
+                });
+            }
+
+            height += 1;
+        }
+
+        if cells.is_empty() {
+            return Err(GridParseError::Empty);
+        }
+
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index_of(x, y).map(move |i| &mut self.cells[i])
+    }
+
+    /// Like [`Grid::get`] but with signed coordinates, negative ones are outside.
+    pub fn get_signed(&self, x: isize, y: isize) -> Option<&T> {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) => self.get(x, y),
+            _ => None,
+        }
+    }
+
+    /// The cell at `x`, `y` with the grid repeating in both directions.
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self[(x, y)]
+    }
+
+    /// The cell at `x`, `y` moved to the nearest edge if it is outside.
+    pub fn get_clamped(&self, x: isize, y: isize) -> &T {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        &self[(x, y)]
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// The rows as slices, from the top. A grid without columns still has `height` rows,
+    /// they are empty.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    fn offset(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        let x = usize::try_from(x as isize + dx).ok()?;
+        let y = usize::try_from(y as isize + dy).ok()?;
+        self.index_of(x, y).map(|_| (x, y))
+    }
+
+    /// The positions of the orthogonal neighbours of `x`, `y` that are inside the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS_4
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(x, y, dx, dy))
+    }
+
+    /// The positions of all neighbours of `x`, `y`, diagonals included, that are inside
+    /// the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS_8
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(x, y, dx, dy))
+    }
+
+    /// The positions seen from `x`, `y` looking in the direction `dx`, `dy`, nearest
+    /// first, until the edge of the grid.
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut pos = (x, y);
+        std::iter::from_fn(move || {
+            if dx == 0 && dy == 0 {
+                return None;
+            }
+            pos = self.offset(pos.0, pos.1, dx, dy)?;
+            Some(pos)
+        })
+    }
+
+    /// The grid as seen after rotating and flipping it.
+    pub fn view(&self, orientation: Orientation) -> View<'_, T> {
+        View {
+            grid: self,
+            orientation,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "position ({}, {}) is outside the {}x{} grid",
+                x, y, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| {
+            panic!(
+                "position ({}, {}) is outside the {}x{} grid",
+                x, y, width, height
+            )
+        })
+    }
+}
+
+fn write_rows<T: Display>(
+    f: &mut fmt::Formatter<'_>,
+    width: usize,
+    height: usize,
+    get: impl Fn(usize, usize) -> T,
+) -> fmt::Result {
+    for y in 0..height {
+        if y > 0 {
+            writeln!(f)?;
+        }
+        for x in 0..width {
+            write!(f, "{}", get(x, y))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// One row per line, without a trailing newline.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_rows(f, self.width, self.height, |x, y| &self[(x, y)])
+    }
+}
+
+/// A rotation by `rotation` quarter turns counter clockwise, followed by a horizontal
+/// flip if `flipped`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Orientation {
+    pub flipped: bool,
+    pub rotation: usize,
+}
+
+impl Orientation {
+    pub fn new(rotation: usize, flipped: bool) -> Self {
+        Self { flipped, rotation }
+    }
+
+    /// The eight distinct orientations.
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        [false, true]
+            .iter()
+            .flat_map(|&flipped| (0..4).map(move |rotation| Orientation { flipped, rotation }))
+    }
+}
+
+/// A rotated and flipped grid, without copying the cells.
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    orientation: Orientation,
+}
+
+impl<'a, T> View<'a, T> {
+    fn is_turned(&self) -> bool {
+        self.orientation.rotation % 2 == 1
+    }
+
+    pub fn width(&self) -> usize {
+        if self.is_turned() {
+            self.grid.height
+        } else {
+            self.grid.width
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        if self.is_turned() {
+            self.grid.width
+        } else {
+            self.grid.height
+        }
+    }
+
+    /// The position in the grid of the position `x`, `y` in the view.
+    pub fn source(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        let (w, h) = (self.grid.width, self.grid.height);
+        let x = if self.orientation.flipped {
+            self.width() - 1 - x
+        } else {
+            x
+        };
+
+        Some(match self.orientation.rotation % 4 {
+            0 => (x, y),
+            1 => (w - 1 - y, x),
+            2 => (w - 1 - x, h - 1 - y),
+            _ => (y, h - 1 - x),
+        })
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        let (x, y) = self.source(x, y)?;
+        self.grid.get(x, y)
+    }
+
+    /// The cells of row `y` of the view, from the left.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &'a T> + '_ {
+        (0..self.width()).filter_map(move |x| self.get(x, y))
+    }
+
+    /// Copies the view into a new grid.
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.width(), self.height(), |x, y| {
+            self.get(x, y).cloned().expect("inside the view")
+        })
+    }
+}
+
+impl<'a, T: Display> Display for View<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_rows(f, self.width(), self.height(), |x, y| {
+            self.get(x, y).expect("inside the view")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456\n");
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(0, 0), Some(&1));
+        assert_eq!(grid.get(2, 1), Some(&6));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid[(1, 1)], 5);
+        assert_eq!(grid.to_string(), "123\n456");
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[1, 2, 3][..], &[4, 5, 6][..]]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let tests = vec![
+            ("", GridParseError::Empty),
+            (
+                "12\n3x",
+                GridParseError::InvalidCell {
+                    line: 2,
+                    column: 2,
+                    cell: 'x',
+                },
+            ),
+            (
+                "12\n345",
+                GridParseError::InvalidWidth {
+                    line: 2,
+                    want: 2,
+                    got: 3,
+                },
+            ),
+            (
+                "\n12\n3x\n",
+                GridParseError::InvalidCell {
+                    line: 3,
+                    column: 2,
+                    cell: 'x',
+                },
+            ),
+            ("\n\n", GridParseError::Empty),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(
+                Grid::parse(input, |c| c.to_digit(10)),
+                Err(want),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = digits("123\n456");
+
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_signed(1, 1), Some(&5));
+        assert_eq!(*grid.get_wrapping(4, -1), 5);
+        assert_eq!(*grid.get_wrapping(-1, 2), 3);
+        assert_eq!(*grid.get_clamped(-5, 9), 4);
+        assert_eq!(*grid.get_clamped(7, -2), 3);
+
+        *grid.get_mut(0, 0).unwrap() = 9;
+        grid[(2, 1)] = 0;
+        assert_eq!(grid.to_string(), "923\n450");
+        assert_eq!(
+            grid,
+            Grid::from_fn(3, 2, |x, y| [[9, 2, 3], [4, 5, 0]][y][x])
+        );
+        assert_eq!(Grid::new(2, 1, 7).to_string(), "77");
+    }
+
+    #[test]
+    fn test_zero_width() {
+        let empty: &[u32] = &[];
+
+        assert_eq!(
+            Grid::new(0, 2, 7).rows().collect::<Vec<_>>(),
+            vec![empty, empty]
+        );
+        assert_eq!(Grid::from_fn(0, 3, |x, y| x + y).rows().count(), 3);
+        assert_eq!(Grid::new(3, 0, 7).rows().count(), 0);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789");
+
+        let values = |positions: Vec<(usize, usize)>| {
+            positions
+                .into_iter()
+                .map(|pos| grid[pos])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(values(grid.neighbours4(1, 1).collect()), vec![2, 6, 8, 4]);
+        assert_eq!(values(grid.neighbours4(0, 0).collect()), vec![2, 4]);
+        assert_eq!(
+            values(grid.neighbours8(1, 1).collect()),
+            vec![2, 3, 6, 9, 8, 7, 4, 1]
+        );
+        assert_eq!(values(grid.neighbours8(2, 2).collect()), vec![6, 8, 5]);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = digits("1234\n5678\n9012");
+
+        let ray = |x, y, dx, dy| {
+            grid.ray(x, y, dx, dy)
+                .map(|pos| grid[pos])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ray(0, 0, 1, 0), vec![2, 3, 4]);
+        assert_eq!(ray(0, 0, 1, 1), vec![6, 1]);
+        assert_eq!(ray(3, 2, -1, -1), vec![7, 2]);
+        assert_eq!(ray(1, 1, 0, -1), vec![2]);
+        assert_eq!(ray(1, 1, 0, 0), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_view() {
+        let grid = digits("123\n456");
+
+        let tests = vec![
+            (0, false, "123\n456"),
+            (1, false, "36\n25\n14"),
+            (2, false, "654\n321"),
+            (3, false, "41\n52\n63"),
+            (0, true, "321\n654"),
+            (1, true, "63\n52\n41"),
+            (2, true, "456\n123"),
+            (3, true, "14\n25\n36"),
+        ];
+
+        for (rotation, flipped, want) in tests {
+            let view = grid.view(Orientation::new(rotation, flipped));
+            assert_eq!(view.to_string(), want, "{} {}", rotation, flipped);
+            assert_eq!(view.to_grid().to_string(), want);
+
+            for (x, y) in view.to_grid().positions() {
+                let (sx, sy) = view.source(x, y).unwrap();
+                assert_eq!(view.get(x, y), Some(&grid[(sx, sy)]));
+            }
+        }
+
+        assert_eq!(
+            grid.view(Orientation::new(1, false))
+                .row(0)
+                .collect::<Vec<_>>(),
+            vec![&3, &6]
+        );
+        assert_eq!(Orientation::all().count(), 8);
+    }
+}
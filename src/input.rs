@@ -0,0 +1,140 @@
+//! Loading the puzzle input of a day.
+
+use color_eyre::Section;
+use eyre::{Result, WrapErr};
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Where the puzzle input for a day is read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    /// `<data_dir>/dayN.txt`
+    Default,
+    /// `-`, the input is read from stdin
+    Stdin,
+    Path(PathBuf),
+    /// `<data_dir>/<profile>/dayN.txt`
+    Profile(String),
+}
+
+impl InputSource {
+    pub fn from_arg(input: Option<&Path>) -> Self {
+        match input {
+            None => InputSource::Default,
+            Some(path) if path == Path::new("-") => InputSource::Stdin,
+            Some(path) => InputSource::Path(path.to_path_buf()),
+        }
+    }
+
+    pub fn load(&self, data_dir: &Path, day: u8) -> Result<String> {
+        match self {
+            InputSource::Default => {
+                read_file(&default_path(data_dir, day), day).suggestion(format!(
+                    "add the puzzle input as {} or pass it with --input <path>",
+                    default_path(data_dir, day).display()
+                ))
+            }
+            InputSource::Stdin => read_stdin(day),
+            InputSource::Path(path) => read_file(path, day),
+            InputSource::Profile(profile) => {
+                read_file(&default_path(&data_dir.join(profile), day), day)
+            }
+        }
+    }
+
+    /// The file the input is read from, `None` for stdin.
+    pub fn path(&self, data_dir: &Path, day: u8) -> Option<PathBuf> {
+        match self {
+            InputSource::Default => Some(default_path(data_dir, day)),
+            InputSource::Stdin => None,
+            InputSource::Path(path) => Some(path.clone()),
+            InputSource::Profile(profile) => Some(default_path(&data_dir.join(profile), day)),
+        }
+    }
+
+    /// The name of the profile the input is from, if any.
+    pub fn profile(&self) -> Option<&str> {
+        match self {
+            InputSource::Profile(profile) => Some(profile),
+            _ => None,
+        }
+    }
+}
+
+pub fn default_path(data_dir: &Path, day: u8) -> PathBuf {
+    data_dir.join(format!("day{}.txt", day))
+}
+
+fn read_file(path: &Path, day: u8) -> Result<String> {
+    fs::read_to_string(path).wrap_err_with(|| {
+        format!(
+            "failed to read input for day {} from {}",
+            day,
+            path.display()
+        )
+    })
+}
+
+fn read_stdin(day: u8) -> Result<String> {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .wrap_err_with(|| format!("failed to read input for day {} from stdin", day))?;
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(InputSource::from_arg(None), InputSource::Default);
+        assert_eq!(
+            InputSource::from_arg(Some(Path::new("-"))),
+            InputSource::Stdin
+        );
+        assert_eq!(
+            InputSource::from_arg(Some(Path::new("data/day1_test.txt"))),
+            InputSource::Path("data/day1_test.txt".into())
+        );
+    }
+
+    #[test]
+    fn test_load_default() -> Result<()> {
+        let input = InputSource::Default.load(Path::new("data"), 15)?;
+        assert_eq!(input, include_str!("../data/day15.txt"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_profile() {
+        let source = InputSource::Profile("alice".into());
+        assert_eq!(
+            source.path(Path::new("data"), 3),
+            Some(PathBuf::from("data/alice/day3.txt"))
+        );
+
+        let err = source.load(Path::new("data"), 3).unwrap_err();
+        assert!(
+            err.to_string().contains("data/alice/day3.txt"),
+            "error should contain the path: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_load_missing() {
+        let err = InputSource::Path("data/day99.txt".into())
+            .load(Path::new("data"), 99)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("data/day99.txt"),
+            "error should contain the path: {}",
+            err
+        );
+    }
+}
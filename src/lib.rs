@@ -0,0 +1,198 @@
+//! Solutions for [Advent of Code 2020](https://adventofcode.com/2020).
+//!
+//! Every day is a module with a [`Solution`] for it, along with the parsers and types
+//! the solution is built from. The days are registered in a table that [`get_day`] and
+//! [`solve`] look them up in, the `aoc2020` binary is a command line front end for it.
+//!
+//! ```
+//! use aoc2020::{day1::Day1, Solution};
+//!
+//! let expenses = Day1.parse("1721\n979\n366\n299\n675\n1456\n")?;
+//! assert_eq!(Day1.part_1(&expenses)?, 514579);
+//! # Ok::<(), eyre::Report>(())
+//! ```
+
+use cache::Cache;
+use eyre::{eyre, Result};
+use output::Record;
+use std::{sync::Mutex, time::Instant};
+use tracing::info_span;
+
+pub use solution::{DynSolution, Part, Solution};
+
+pub mod answers;
+pub mod bench;
+pub mod cache;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod examples;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod logging;
+pub mod output;
+pub mod parse;
+pub mod profile;
+pub mod scaffold;
+pub mod solution;
+pub mod visualize;
+pub mod watch;
+
+#[cfg(test)]
+use ctor::ctor;
+
+#[ctor]
+#[cfg(test)]
+fn init_test() {
+    logging::init(&logging::LogOptions::new("debug")).unwrap();
+}
+
+static SOLUTIONS: &[(u8, &dyn DynSolution)] = &[
+    (1, &day1::Day1),
+    (2, &day2::Day2),
+    (3, &day3::Day3),
+    (4, &day4::Day4),
+    (5, &day5::Day5),
+    (6, &day6::Day6),
+    (7, &day7::Day7),
+    (8, &day8::Day8),
+    (9, &day9::Day9),
+    (10, &day10::Day10),
+    (11, &day11::Day11),
+    (12, &day12::Day12),
+    (13, &day13::Day13),
+    (14, &day14::Day14),
+    (15, &day15::Day15),
+    (16, &day16::Day16),
+    (17, &day17::Day17),
+    (18, &day18::Day18),
+    (19, &day19::Day19),
+    (20, &day20::Day20),
+];
+
+/// The days that have a solution, in order.
+pub fn days() -> Vec<u8> {
+    SOLUTIONS.iter().map(|(day, _)| *day).collect()
+}
+
+/// The solution for `day`, if there is one.
+pub fn get_day(day: u8) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|(_, solution)| *solution)
+}
+
+/// Parses the input once and solves each of the parts, if the input can not be loaded or
+/// parsed every part fails with that error.
+pub fn solve(day: u8, input: Result<String>, parts: &[Part]) -> Vec<Record> {
+    let span = info_span!("day", day);
+    let _enter = span.enter();
+
+    let parsed = input.and_then(|input| {
+        let solution = get_day(day).ok_or_else(|| eyre!("day {} does not exist", day))?;
+
+        let span = info_span!("parse");
+        let _enter = span.enter();
+        Ok((solution, solution.parse_input(&input)?))
+    });
+
+    // every part fails with the same error, keep the report so its span trace and the
+    // snippet of where parsing failed are not lost
+    let parsed = parsed.map_err(|e| (format!("{:#}", e), format!("{:?}", e)));
+
+    parts
+        .iter()
+        .map(|&part| {
+            let span = info_span!("run", %part);
+            let _enter = span.enter();
+
+            let now = Instant::now();
+            match &parsed {
+                Ok((solution, parsed)) => {
+                    let result = solution.solve(parsed, part);
+                    Record::new(day, part, result, now.elapsed())
+                }
+                Err((error, report)) => Record {
+                    report: Some(report.clone()),
+                    ..Record::new(day, part, Err(eyre!("{}", error)), now.elapsed())
+                },
+            }
+        })
+        .collect()
+}
+
+/// Like [`solve`], but the parts with an answer in `cache` for the same input are not
+/// solved again, unless `refresh` is set, and the new answers are added to it.
+pub fn solve_cached(
+    day: u8,
+    input: Result<String>,
+    parts: &[Part],
+    cache: &Mutex<Cache>,
+    refresh: bool,
+) -> Vec<Record> {
+    let input = match input {
+        Ok(input) => input,
+        Err(e) => return solve(day, Err(e), parts),
+    };
+
+    let cached: Vec<Option<String>> = {
+        let cache = cache.lock().unwrap();
+        parts
+            .iter()
+            .map(|&part| {
+                if refresh {
+                    None
+                } else {
+                    cache.get(day, part, &input).map(String::from)
+                }
+            })
+            .collect()
+    };
+
+    let unsolved: Vec<Part> = parts
+        .iter()
+        .zip(&cached)
+        .filter(|(_, answer)| answer.is_none())
+        .map(|(&part, _)| part)
+        .collect();
+    let mut solved = if unsolved.is_empty() {
+        Vec::new().into_iter()
+    } else {
+        solve(day, Ok(input.clone()), &unsolved).into_iter()
+    };
+
+    let mut cache = cache.lock().unwrap();
+    parts
+        .iter()
+        .zip(cached)
+        .map(|(&part, answer)| match answer {
+            Some(answer) => Record::cached(day, part, &answer),
+            None => {
+                let record = solved.next().expect("every unsolved part is solved");
+                if let Some(answer) = &record.answer {
+                    cache.insert(day, part, &input, answer);
+                }
+                record
+            }
+        })
+        .collect()
+}
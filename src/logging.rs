@@ -0,0 +1,170 @@
+//! Setting up the tracing subscriber: the log format, where the logs go and whether
+//! spans report how long they took.
+
+use eyre::{Result, WrapErr};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+};
+use thiserror::Error;
+use tracing_error::ErrorLayer;
+use tracing_subscriber::{
+    fmt::{self, format::FmtSpan},
+    prelude::*,
+    EnvFilter, Layer, Registry,
+};
+
+/// How log lines are formatted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    /// One line per event with the span context
+    Full,
+    /// Multi-line, for reading by a human
+    Pretty,
+    /// One short line per event
+    Compact,
+    /// One JSON object per event
+    Json,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LogFormatParseError {
+    #[error("invalid log format: '{0}', expected full, pretty, compact or json")]
+    InvalidLogFormat(String),
+}
+
+impl FromStr for LogFormat {
+    type Err = LogFormatParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "full" => Ok(LogFormat::Full),
+            "pretty" => Ok(LogFormat::Pretty),
+            "compact" => Ok(LogFormat::Compact),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(LogFormatParseError::InvalidLogFormat(s.to_string())),
+        }
+    }
+}
+
+/// What to log, how and where.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogOptions {
+    /// The filter used when `RUST_LOG` is not set, e.g. `info` or `aoc2020::day19=trace`
+    pub level: String,
+    pub format: LogFormat,
+    /// Append the logs to this file instead of writing them to stderr
+    pub file: Option<PathBuf>,
+    /// Log an event with the busy and idle time of every span when it closes
+    pub span_timing: bool,
+}
+
+impl LogOptions {
+    pub fn new(level: &str) -> Self {
+        Self {
+            level: level.to_string(),
+            format: LogFormat::Full,
+            file: None,
+            span_timing: false,
+        }
+    }
+}
+
+/// Where the formatted logs are written.
+#[derive(Clone)]
+enum LogWriter {
+    Stderr,
+    File(Arc<File>),
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            LogWriter::Stderr => io::stderr().write(buf),
+            LogWriter::File(file) => (&**file).write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            LogWriter::Stderr => io::stderr().flush(),
+            LogWriter::File(file) => (&**file).flush(),
+        }
+    }
+}
+
+fn fmt_layer(options: &LogOptions, writer: LogWriter) -> Box<dyn Layer<Registry> + Send + Sync> {
+    let span_events = if options.span_timing {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    };
+    let ansi = matches!(writer, LogWriter::Stderr);
+
+    let layer = fmt::layer()
+        .with_target(false)
+        .with_span_events(span_events)
+        .with_writer(move || writer.clone());
+
+    match options.format {
+        LogFormat::Full => Box::new(layer.with_ansi(ansi)),
+        LogFormat::Pretty => Box::new(layer.pretty().with_ansi(ansi)),
+        LogFormat::Compact => Box::new(layer.compact().with_ansi(ansi)),
+        LogFormat::Json => Box::new(layer.json().with_current_span(true)),
+    }
+}
+
+/// Installs the color-eyre error reports and a tracing subscriber set up by `options`,
+/// `RUST_LOG` overrides `options.level`.
+pub fn init(options: &LogOptions) -> Result<()> {
+    color_eyre::install()?;
+
+    let writer = match &options.file {
+        Some(path) => LogWriter::File(Arc::new(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .wrap_err_with(|| format!("failed to open log file {}", path.display()))?,
+        )),
+        None => LogWriter::Stderr,
+    };
+
+    let filter_layer = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&options.level))
+        .wrap_err_with(|| format!("invalid log filter: '{}'", options.level))?;
+
+    tracing_subscriber::registry()
+        .with(fmt_layer(options, writer))
+        .with(filter_layer)
+        .with(ErrorLayer::default())
+        .try_init()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_format() {
+        let tests = vec![
+            ("full", Ok(LogFormat::Full)),
+            ("pretty", Ok(LogFormat::Pretty)),
+            (" compact", Ok(LogFormat::Compact)),
+            ("json", Ok(LogFormat::Json)),
+            (
+                "xml",
+                Err(LogFormatParseError::InvalidLogFormat("xml".into())),
+            ),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(LogFormat::from_str(input), want, "{}", input);
+        }
+    }
+}
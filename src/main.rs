@@ -1,77 +1,614 @@
-use eyre::Result;
-use tracing_error::ErrorLayer;
-use tracing_subscriber::prelude::*;
-use tracing_subscriber::{fmt, EnvFilter};
-
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-fn init_tracing(level: &str) {
-    color_eyre::install().unwrap();
-
-    let fmt_layer = fmt::layer().with_target(false);
-
-    let filter_layer = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new(level))
-        .unwrap();
-
-    tracing_subscriber::registry()
-        .with(filter_layer)
-        .with(fmt_layer)
-        .with(ErrorLayer::default())
-        .init();
+use aoc2020::{
+    answers::{Answers, Verdict},
+    bench,
+    cache::{self, Cache},
+    day1,
+    day2::{self, PasswordPolicy},
+    day3::{self, Slope},
+    examples, generate, get_day,
+    input::{self, InputSource},
+    logging::{self, LogOptions},
+    output::{self, Format, Record},
+    profile, scaffold, solve, solve_cached, visualize,
+    watch::{self, WatchedFile},
+    Part,
+};
+use cli::{CacheMode, Command, DaySelection, Opt};
+use eyre::{bail, eyre, Result};
+use rayon::{prelude::*, ThreadPoolBuilder};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::Duration,
+};
+use structopt::StructOpt;
+use tracing::info_span;
+
+mod cli;
+
+/// Runs the days on each of the `inputs`, which are either a single source or one source
+/// per input profile.
+fn run(
+    days: &DaySelection,
+    part: Option<Part>,
+    inputs: &[InputSource],
+    data_dir: &Path,
+    format: Format,
+    jobs: usize,
+    cache_mode: &CacheMode,
+) -> Result<()> {
+    let parts = match part {
+        Some(part) => vec![part],
+        None => Part::all().to_vec(),
+    };
+
+    let profiles: Vec<&str> = inputs.iter().filter_map(InputSource::profile).collect();
+    let all_days = *days == DaySelection::All;
+    let days = days.resolve(&aoc2020::days())?;
+    if days.len() > 1
+        && inputs
+            .iter()
+            .any(|input| matches!(input, InputSource::Stdin | InputSource::Path(_)))
+    {
+        bail!("--input can only be used when running a single day");
+    }
+
+    // a profile does not need an input for every day when running all of them
+    let runs: Vec<(&InputSource, u8)> = inputs
+        .iter()
+        .flat_map(|input| days.iter().map(move |&day| (input, day)))
+        .filter(|(input, day)| {
+            !all_days
+                || input.profile().is_none()
+                || input.path(data_dir, *day).is_none_or(|path| path.exists())
+        })
+        .collect();
+
+    if let Some(header) = format.header(!profiles.is_empty()) {
+        println!("{}", header);
+    }
+
+    let cache = match cache_mode.path() {
+        Some(path) => Some(Mutex::new(Cache::load(path, &cache::binary_version()?)?)),
+        None => None,
+    };
+    let refresh = matches!(cache_mode, CacheMode::Refresh(_));
+
+    let pool = ThreadPoolBuilder::new().num_threads(jobs).build()?;
+    let results: Vec<Vec<Record>> = pool.install(|| {
+        runs.par_iter()
+            .map(|&(source, day)| {
+                let input = source.load(data_dir, day);
+                let records = match &cache {
+                    Some(cache) => solve_cached(day, input, &parts, cache, refresh),
+                    None => solve(day, input, &parts),
+                };
+                match source.profile() {
+                    Some(profile) => records
+                        .into_iter()
+                        .map(|record| record.with_profile(profile))
+                        .collect(),
+                    None => records,
+                }
+            })
+            .collect()
+    });
+
+    if let (Some(cache), Some(path)) = (cache, cache_mode.path()) {
+        cache.into_inner().unwrap().save(path)?;
+    }
+
+    let records: Vec<Record> = results.into_iter().flatten().collect();
+    if format == Format::Plain && !profiles.is_empty() {
+        let profiles: Vec<String> = profiles.iter().map(|profile| profile.to_string()).collect();
+        println!("{}", profile::side_by_side(&profiles, &records));
+    } else {
+        for record in &records {
+            println!("{}", format.format(record)?);
+        }
+    }
+
+    if let Some(summary) = output::summary(&records) {
+        let failed = records
+            .iter()
+            .filter(|record| record.error.is_some())
+            .count();
+
+        eprintln!("\n{}\n", summary);
+        bail!("{} of {} parts failed", failed, records.len());
+    }
+
+    Ok(())
 }
 
-#[cfg(test)]
-use ctor::ctor;
+fn verify(days: &DaySelection, data_dir: &Path, answers_path: &Path, record: bool) -> Result<()> {
+    let mut answers = Answers::load(answers_path)?;
+    let mut mismatches = 0;
+    let mut failures = 0;
+    let mut recorded = 0;
 
-#[ctor]
-#[cfg(test)]
-fn init_test() {
-    init_tracing("debug");
+    println!(
+        "{:>3}  {:>4}  {:<8}  {:<20}  expected",
+        "day", "part", "status", "answer"
+    );
+
+    for day in days.resolve(&aoc2020::days())? {
+        let parts = Part::all();
+        let results = solve(day, InputSource::Default.load(data_dir, day), &parts);
+
+        for (part, result) in parts.iter().cloned().zip(results) {
+            let answer = match result.answer {
+                Some(answer) => answer,
+                None => {
+                    println!(
+                        "{:>3}  {:>4}  {:<8}  {}",
+                        day,
+                        part,
+                        "error",
+                        result.error.unwrap_or_default()
+                    );
+                    failures += 1;
+                    continue;
+                }
+            };
+
+            let verdict = answers.check(day, part, &answer);
+            let expected = match &verdict {
+                Verdict::Match => answer.as_str(),
+                Verdict::Mismatch(expected) => expected.as_str(),
+                Verdict::Missing => "",
+            };
+            println!(
+                "{:>3}  {:>4}  {:<8}  {:<20}  {}",
+                day, part, verdict, answer, expected
+            );
+
+            match verdict {
+                Verdict::Match => (),
+                Verdict::Mismatch(_) => mismatches += 1,
+                Verdict::Missing if record => {
+                    answers.set(day, part, &answer);
+                    recorded += 1;
+                }
+                Verdict::Missing => (),
+            }
+        }
+    }
+
+    if recorded > 0 {
+        answers.save(answers_path)?;
+        println!(
+            "recorded {} answers in {}",
+            recorded,
+            answers_path.display()
+        );
+    }
+
+    match (failures, mismatches) {
+        (0, 0) => Ok(()),
+        (0, mismatches) => bail!("{} answers do not match the recorded ones", mismatches),
+        (failures, 0) => bail!("{} parts failed", failures),
+        (failures, mismatches) => bail!(
+            "{} parts failed and {} answers do not match the recorded ones",
+            failures,
+            mismatches
+        ),
+    }
 }
 
-fn main() -> Result<()> {
-    init_tracing("info");
-
-    day20::run()?;
-    day19::run()?;
-    day18::run()?;
-    day17::run()?;
-    day16::run()?;
-    day15::run()?;
-    day14::run()?;
-    day13::run()?;
-    day12::run()?;
-    day11::run()?;
-    day10::run()?;
-    day9::run();
-    day8::run();
-    day7::run();
-    day6::run();
-    day5::run();
-    day4::run();
-    day3::run();
-    day2::run();
-    day1::run();
+fn verify_examples(days: &DaySelection, data_dir: &Path) -> Result<()> {
+    let days = days.resolve(&aoc2020::days())?;
+    let mut failures = 0;
+
+    println!(
+        "{:<30}  {:>4}  {:<8}  expected",
+        "example", "part", "status"
+    );
+
+    for example in examples::discover(data_dir)? {
+        if !days.contains(&example.day) {
+            continue;
+        }
+
+        let name = example.path.display();
+        match example.check() {
+            Ok(verdicts) => {
+                for (part, verdict) in verdicts {
+                    let expected = example.expected.get(part).unwrap_or_default();
+                    println!("{:<30}  {:>4}  {:<8}  {}", name, part, verdict, expected);
+
+                    if verdict != Verdict::Match {
+                        failures += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                println!("{:<30}  error: {:#}", name, e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        bail!("{} example answers do not match", failures);
+    }
+
+    Ok(())
+}
+
+fn bench(days: &DaySelection, iterations: usize, data_dir: &Path, json: bool) -> Result<()> {
+    let mut results = Vec::new();
+
+    for day in days.resolve(&aoc2020::days())? {
+        let solution = get_day(day).ok_or_else(|| eyre!("day {} does not exist", day))?;
+        let input = InputSource::Default.load(data_dir, day)?;
+
+        let span = info_span!("bench", day);
+        let _enter = span.enter();
+        results.extend(bench::bench_day(day, solution, &input, iterations)?);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        bench::print_table(&results);
+    }
+
+    Ok(())
+}
+
+fn watch(day: u8, inputs: &[PathBuf], data_dir: &Path, interval: Duration) -> Result<()> {
+    if get_day(day).is_none() {
+        bail!("day {} does not exist", day);
+    }
+
+    let paths = if inputs.is_empty() {
+        vec![input::default_path(data_dir, day)]
+    } else {
+        inputs.to_vec()
+    };
+
+    let mut files: Vec<(WatchedFile, Vec<Record>)> = paths
+        .into_iter()
+        .map(|path| (WatchedFile::new(path), Vec::new()))
+        .collect();
+
+    loop {
+        for (file, previous) in files.iter_mut() {
+            let input = match file.poll() {
+                Some(input) => input,
+                None => continue,
+            };
+
+            println!("{}:", file.path().display());
+            let records = solve(day, input, &Part::all());
+            for record in &records {
+                let previous = previous.iter().find(|r| r.part == record.part);
+                println!("{}", watch::format_change(previous, record));
+            }
+            if let Some(summary) = output::summary(&records) {
+                eprintln!("{}", summary);
+            }
+            *previous = records;
+        }
+
+        thread::sleep(interval);
+    }
+}
+
+fn visualize(
+    day: u8,
+    part: Part,
+    input: &InputSource,
+    data_dir: &Path,
+    delay: Duration,
+) -> Result<()> {
+    let input = input.load(data_dir, day)?;
+    let (width, _) = visualize::terminal_size();
+    let frames = visualize::frames(day, &input, part, width)?;
+
+    visualize::play(frames, delay)
+}
+
+fn expenses(input: &InputSource, data_dir: &Path, k: usize, target: u64, all: bool) -> Result<()> {
+    let report = day1::parse_input(&input.load(data_dir, 1)?)?;
+
+    if !all {
+        println!("{}", report.audit(k, target)?);
+        return Ok(());
+    }
+
+    let combinations = day1::find_k_sum(&report.entries, k, target);
+    if combinations.is_empty() {
+        bail!("no {} entries sum to {}", k, target);
+    }
+    for indices in &combinations {
+        println!("{}", day1::format_sum(&report.combination(indices), target));
+    }
+
+    Ok(())
+}
+
+fn passwords(
+    input: &InputSource,
+    data_dir: &Path,
+    policies: Vec<Box<dyn PasswordPolicy>>,
+    report: bool,
+    diff: bool,
+) -> Result<()> {
+    let entries = day2::parse_input(&input.load(data_dir, 2)?)?;
+    let policies = if policies.is_empty() {
+        vec![
+            Box::new(day2::CountPolicy) as Box<dyn PasswordPolicy>,
+            Box::new(day2::PositionPolicy),
+        ]
+    } else {
+        policies
+    };
+
+    if report {
+        println!("{}\n", day2::report(&entries, &policies));
+    }
+    if diff {
+        let (a, b) = match policies.as_slice() {
+            [a, b] => (a.as_ref(), b.as_ref()),
+            _ => bail!("--diff compares two policies, got {}", policies.len()),
+        };
+        let lines = day2::diff(&entries, a, b);
+        if !lines.is_empty() {
+            println!("{}\n", lines);
+        }
+    }
+
+    for policy in &policies {
+        println!(
+            "{}: {} of {} valid",
+            policy.name(),
+            day2::count_valid(&entries, policy.as_ref()),
+            entries.len()
+        );
+    }
+
+    Ok(())
+}
+
+fn slopes(
+    input: &InputSource,
+    data_dir: &Path,
+    slopes: &[Slope],
+    sweep: Option<(usize, usize)>,
+) -> Result<()> {
+    let map = day3::parse_input(&input.load(data_dir, 3)?)?;
+
+    let stats = match sweep {
+        Some((max_right, max_down)) => day3::sweep(&map, 0..=max_right, 1..=max_down)?,
+        None if slopes.is_empty() => day3::PART_2_SLOPES
+            .iter()
+            .map(|&slope| day3::evaluate(&map, slope))
+            .collect::<Result<_>>()?,
+        None => slopes
+            .iter()
+            .map(|&slope| day3::evaluate(&map, slope))
+            .collect::<Result<_>>()?,
+    };
+    if stats.is_empty() {
+        bail!("no slopes to go down");
+    }
+
+    println!("{}\n", day3::format_table(&stats));
+    if let (Some(fewest), Some(most)) = (day3::fewest_trees(&stats), day3::most_trees(&stats)) {
+        println!("fewest trees: {} on {}", fewest.trees, fewest.slope);
+        println!("most trees: {} on {}", most.trees, most.slope);
+    }
+    if sweep.is_none() {
+        let product = stats
+            .iter()
+            .try_fold(1_usize, |product, stats| product.checked_mul(stats.trees));
+        match product {
+            Some(product) => println!("product: {}", product),
+            None => println!("product: too large"),
+        }
+    }
+
+    Ok(())
+}
+
+fn gen(day: u8, size: Option<usize>, seed: u64) -> Result<()> {
+    print!("{}", generate::generate(day, size, seed)?);
 
     Ok(())
 }
+
+fn new_day(day: u8, root: &Path) -> Result<()> {
+    if get_day(day).is_some() {
+        bail!("day {} already exists", day);
+    }
+
+    for path in scaffold::new_day(root, day)? {
+        println!("created {}", path.display());
+    }
+    println!(
+        "registered day {} in {}",
+        day,
+        root.join("src").join("lib.rs").display()
+    );
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    logging::init(&LogOptions {
+        level: opt.log_level.clone(),
+        format: opt.log_format,
+        file: opt.log_file.clone(),
+        span_timing: opt.log_spans,
+    })?;
+
+    match opt.cmd {
+        Some(Command::Run {
+            days,
+            part,
+            input,
+            data_dir,
+            profile: profiles,
+            format,
+            jobs,
+            cache,
+            no_cache,
+        }) => {
+            let inputs = if profiles.is_empty() {
+                vec![InputSource::from_arg(input.as_deref())]
+            } else {
+                profile::resolve(&data_dir, &profiles)?
+                    .into_iter()
+                    .map(InputSource::Profile)
+                    .collect()
+            };
+
+            run(
+                &days,
+                part,
+                &inputs,
+                &data_dir,
+                format,
+                jobs,
+                &CacheMode::new(cache, no_cache),
+            )
+        }
+        Some(Command::Verify {
+            days,
+            data_dir,
+            answers,
+            record,
+            examples: false,
+        }) => verify(&days, &data_dir, &answers, record),
+        Some(Command::Verify {
+            days,
+            data_dir,
+            examples: true,
+            ..
+        }) => verify_examples(&days, &data_dir),
+        Some(Command::Bench {
+            days,
+            iterations,
+            data_dir,
+            json,
+        }) => bench(&days, iterations, &data_dir, json),
+        Some(Command::Watch {
+            day,
+            input,
+            data_dir,
+            interval,
+        }) => watch(day, &input, &data_dir, Duration::from_millis(interval)),
+        Some(Command::Visualize {
+            day,
+            part,
+            input,
+            data_dir,
+            delay,
+        }) => visualize(
+            day,
+            part,
+            &InputSource::from_arg(input.as_deref()),
+            &data_dir,
+            Duration::from_millis(delay),
+        ),
+        Some(Command::Expenses {
+            k,
+            target,
+            all,
+            input,
+            data_dir,
+        }) => expenses(
+            &InputSource::from_arg(input.as_deref()),
+            &data_dir,
+            k,
+            target,
+            all,
+        ),
+        Some(Command::Passwords {
+            policy,
+            report,
+            diff,
+            input,
+            data_dir,
+        }) => passwords(
+            &InputSource::from_arg(input.as_deref()),
+            &data_dir,
+            policy,
+            report,
+            diff,
+        ),
+        Some(Command::Slopes {
+            slope,
+            sweep,
+            max_right,
+            max_down,
+            input,
+            data_dir,
+        }) => slopes(
+            &InputSource::from_arg(input.as_deref()),
+            &data_dir,
+            &slope,
+            if sweep {
+                Some((max_right, max_down))
+            } else {
+                None
+            },
+        ),
+        Some(Command::Gen { day, size, seed }) => gen(day, size, seed),
+        Some(Command::New { day, root }) => new_day(day, &root),
+        None => run(
+            &DaySelection::All,
+            None,
+            &[InputSource::Default],
+            Path::new("data"),
+            Format::Plain,
+            1,
+            &CacheMode::Off,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    #[test]
+    fn test_verify_broken_input() -> Result<()> {
+        let data_dir = env::temp_dir().join(format!("aoc2020_verify_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir)?;
+        fs::write(
+            data_dir.join("day1.txt"),
+            "1721\n979\n366\n299\n675\n1456\n",
+        )?;
+        fs::write(data_dir.join("day2.txt"), "garbage\n")?;
+        fs::write(data_dir.join("day3.txt"), "..#\n#..\n.#.\n")?;
+        let answers_path = data_dir.join("answers.toml");
+
+        let days: DaySelection = "1..=3".parse()?;
+        let e = verify(&days, &data_dir, &answers_path, true).unwrap_err();
+        assert_eq!(e.to_string(), "2 parts failed");
+
+        let answers = Answers::load(&answers_path)?;
+        assert_eq!(
+            answers.get(1, Part::One),
+            Some("514579"),
+            "the days before the broken one are recorded"
+        );
+        assert_eq!(answers.get(2, Part::One), None);
+        assert_eq!(
+            answers.get(3, Part::One),
+            Some("1"),
+            "the days after the broken one are checked"
+        );
+
+        fs::remove_dir_all(&data_dir)?;
+        Ok(())
+    }
+}
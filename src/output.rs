@@ -0,0 +1,319 @@
+//! Printing the results of a run as plain text, JSON or CSV.
+
+use crate::solution::Part;
+use eyre::Result;
+use serde::Serialize;
+use std::{str::FromStr, time::Duration};
+use thiserror::Error;
+
+/// How the results of a run are printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// `day 1 part 2: 42275090`
+    Plain,
+    /// One JSON object per line
+    Json,
+    /// Comma separated, with a header row
+    Csv,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum FormatParseError {
+    #[error("invalid format: '{0}', expected plain, json or csv")]
+    InvalidFormat(String),
+}
+
+impl FromStr for Format {
+    type Err = FormatParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "plain" => Ok(Format::Plain),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(FormatParseError::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+/// The outcome of solving one part of a day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    /// Time spent solving the part, not including parsing
+    pub elapsed_ms: f64,
+    pub error: Option<String>,
+    /// The answer is from the cache, the part was not solved
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// The input profile the input is from, if it is not the default input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// The whole report of the error, with its sections and span trace, as it is printed
+    /// by [`summary`]
+    #[serde(skip)]
+    pub report: Option<String>,
+}
+
+impl Record {
+    pub fn new(day: u8, part: Part, result: Result<String>, elapsed: Duration) -> Self {
+        let (answer, error, report) = match result {
+            Ok(answer) => (Some(answer), None, None),
+            Err(e) => (None, Some(format!("{:#}", e)), Some(format!("{:?}", e))),
+        };
+
+        Self {
+            day,
+            part: match part {
+                Part::One => 1,
+                Part::Two => 2,
+            },
+            answer,
+            elapsed_ms: elapsed.as_secs_f64() * 1000.0,
+            error,
+            cached: false,
+            profile: None,
+            report,
+        }
+    }
+
+    /// A record of an answer from the cache.
+    pub fn cached(day: u8, part: Part, answer: &str) -> Self {
+        Self {
+            cached: true,
+            ..Self::new(day, part, Ok(answer.to_string()), Duration::default())
+        }
+    }
+
+    /// The record for the input of `profile`.
+    pub fn with_profile(self, profile: &str) -> Self {
+        Self {
+            profile: Some(profile.to_string()),
+            ..self
+        }
+    }
+}
+
+impl Format {
+    /// The line printed before the first record, if any. The CSV records of a run with
+    /// input profiles end with a `profile` column.
+    pub fn header(&self, profiles: bool) -> Option<&'static str> {
+        match self {
+            Format::Csv if profiles => Some("day,part,answer,elapsed_ms,error,profile"),
+            Format::Csv => Some("day,part,answer,elapsed_ms,error"),
+            Format::Plain | Format::Json => None,
+        }
+    }
+
+    pub fn format(&self, record: &Record) -> Result<String> {
+        let output = match self {
+            Format::Plain => match (&record.answer, &record.error) {
+                (_, Some(error)) => {
+                    format!("day {} part {}: error: {}", record.day, record.part, error)
+                }
+                (Some(answer), None) if record.cached => {
+                    format!(
+                        "day {} part {}: {} (cached)",
+                        record.day, record.part, answer
+                    )
+                }
+                (Some(answer), None) => {
+                    format!("day {} part {}: {}", record.day, record.part, answer)
+                }
+                (None, None) => format!("day {} part {}:", record.day, record.part),
+            },
+            Format::Json => serde_json::to_string(record)?,
+            Format::Csv => {
+                let mut line = format!(
+                    "{},{},{},{:.3},{}",
+                    record.day,
+                    record.part,
+                    csv_field(record.answer.as_deref().unwrap_or_default()),
+                    record.elapsed_ms,
+                    csv_field(record.error.as_deref().unwrap_or_default())
+                );
+                if let Some(profile) = &record.profile {
+                    line += &format!(",{}", csv_field(profile));
+                }
+                line
+            }
+        };
+
+        Ok(output)
+    }
+}
+
+/// The reports of the failed parts, for printing after the results. The parts of a day
+/// that failed with the same report, like a parse error, share one entry. `None` if no
+/// part failed.
+pub fn summary(records: &[Record]) -> Option<String> {
+    let mut failures: Vec<(&Record, Vec<u8>)> = Vec::new();
+    for record in records.iter().filter(|record| record.error.is_some()) {
+        match failures.last_mut() {
+            Some((first, parts))
+                if first.day == record.day
+                    && first.profile == record.profile
+                    && first.report == record.report =>
+            {
+                parts.push(record.part)
+            }
+            _ => failures.push((record, vec![record.part])),
+        }
+    }
+
+    if failures.is_empty() {
+        return None;
+    }
+
+    let entries: Vec<String> = failures
+        .iter()
+        .map(|(record, parts)| {
+            let parts = match parts.as_slice() {
+                [part] => format!("part {}", part),
+                [first @ .., last] => format!(
+                    "parts {} and {}",
+                    first
+                        .iter()
+                        .map(|part| part.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    last
+                ),
+                [] => unreachable!("every failure has a part"),
+            };
+            let profile = match &record.profile {
+                Some(profile) => format!(" for profile {}", profile),
+                None => String::new(),
+            };
+            let report = record
+                .report
+                .as_deref()
+                .or(record.error.as_deref())
+                .unwrap_or_default();
+
+            format!(
+                "day {} {} failed{}:\n{}",
+                record.day, parts, profile, report
+            )
+        })
+        .collect();
+
+    Some(entries.join("\n\n"))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::eyre;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record::new(1, Part::One, Ok("751776".into()), Duration::from_micros(82)),
+            Record::new(
+                8,
+                Part::Two,
+                Err(eyre!("no correction, \"nop\" or \"jmp\"")),
+                Duration::from_millis(2),
+            ),
+            Record::cached(15, Part::Two, "18929178"),
+            Record::new(
+                1,
+                Part::One,
+                Ok("1016619".into()),
+                Duration::from_micros(95),
+            )
+            .with_profile("bob"),
+        ]
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(Format::from_str("plain"), Ok(Format::Plain));
+        assert_eq!(Format::from_str("json"), Ok(Format::Json));
+        assert_eq!(Format::from_str("csv"), Ok(Format::Csv));
+        assert!(Format::from_str("yaml").is_err());
+    }
+
+    #[test]
+    fn test_format() -> Result<()> {
+        let tests = vec![
+            (
+                Format::Plain,
+                vec![
+                    "day 1 part 1: 751776",
+                    "day 8 part 2: error: no correction, \"nop\" or \"jmp\"",
+                    "day 15 part 2: 18929178 (cached)",
+                    "day 1 part 1: 1016619",
+                ],
+            ),
+            (
+                Format::Json,
+                vec![
+                    r#"{"day":1,"part":1,"answer":"751776","elapsed_ms":0.082,"error":null}"#,
+                    r#"{"day":8,"part":2,"answer":null,"elapsed_ms":2.0,"error":"no correction, \"nop\" or \"jmp\""}"#,
+                    r#"{"day":15,"part":2,"answer":"18929178","elapsed_ms":0.0,"error":null,"cached":true}"#,
+                    r#"{"day":1,"part":1,"answer":"1016619","elapsed_ms":0.095,"error":null,"profile":"bob"}"#,
+                ],
+            ),
+            (
+                Format::Csv,
+                vec![
+                    "1,1,751776,0.082,",
+                    r#"8,2,,2.000,"no correction, ""nop"" or ""jmp""""#,
+                    "15,2,18929178,0.000,",
+                    "1,1,1016619,0.095,,bob",
+                ],
+            ),
+        ];
+
+        for (format, want) in tests {
+            let got = records()
+                .iter()
+                .map(|record| format.format(record))
+                .collect::<Result<Vec<_>>>()?;
+            assert_eq!(got, want, "{:?}", format);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(summary(&records()[..1]), None);
+        assert!(records()[1]
+            .report
+            .as_deref()
+            .unwrap_or_default()
+            .contains("no correction, \"nop\" or \"jmp\""));
+
+        let failed = |day, part, profile: Option<&str>, report: &str| Record {
+            profile: profile.map(String::from),
+            report: Some(report.to_string()),
+            ..Record::new(day, part, Err(eyre!("failed")), Duration::default())
+        };
+        let records = vec![
+            Record::new(1, Part::One, Ok("751776".into()), Duration::from_micros(82)),
+            failed(2, Part::One, None, "invalid input on line 3"),
+            failed(2, Part::Two, None, "invalid input on line 3"),
+            failed(8, Part::Two, Some("bob"), "no correction"),
+        ];
+
+        assert_eq!(
+            summary(&records).as_deref(),
+            Some(
+                "day 2 parts 1 and 2 failed:\ninvalid input on line 3\n\n\
+                 day 8 part 2 failed for profile bob:\nno correction"
+            )
+        );
+    }
+}
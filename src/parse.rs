@@ -0,0 +1,409 @@
+//! Splitting the input into records, lines and fields while keeping track of where each
+//! piece came from, so parse errors can point at the line and column.
+
+use color_eyre::Section;
+use std::{fmt::Display, ops::Range, str::FromStr};
+use thiserror::Error;
+
+/// What went wrong, without the location.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    #[error("expected '{want}', found '{got}'")]
+    Expected { want: String, got: String },
+    #[error("invalid value: '{value}', {reason}")]
+    InvalidValue { value: String, reason: String },
+    #[error("unexpected end of input, expected {0}")]
+    UnexpectedEnd(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+/// A parse error and where in the input it happened.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("invalid input on line {line}, column {column}: {kind}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+    source_line: String,
+    width: usize,
+}
+
+impl ParseError {
+    /// The line the error is on with the span marked by carets.
+    pub fn snippet(&self) -> String {
+        let gutter = self.line.to_string().len();
+        format!(
+            "{:>gutter$} |\n{} | {}\n{:>gutter$} | {:>column$}{}",
+            "",
+            self.line,
+            self.source_line,
+            "",
+            "",
+            "^".repeat(self.width),
+            gutter = gutter,
+            column = self.column - 1,
+        )
+    }
+
+    /// Converts the error into a report with the snippet as a section, shown when the
+    /// report is printed with `{:?}`.
+    pub fn into_report(self) -> eyre::Report {
+        let snippet = self.snippet();
+        eyre::Report::new(self).section(snippet)
+    }
+}
+
+/// Turns a `Result<T, ParseError>` into an [`eyre::Result`] with the snippet attached.
+pub trait WithSnippet<T> {
+    fn with_snippet(self) -> eyre::Result<T>;
+}
+
+impl<T> WithSnippet<T> for Result<T, ParseError> {
+    fn with_snippet(self) -> eyre::Result<T> {
+        self.map_err(ParseError::into_report)
+    }
+}
+
+/// A piece of the input, remembering where in the input it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    input: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Span<'a> {
+    /// The whole input.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            start: 0,
+            end: input.len(),
+        }
+    }
+
+    /// The span of `text`, which must be a slice of this span.
+    fn sub(&self, text: &'a str) -> Self {
+        let start = text.as_ptr() as usize - self.input.as_ptr() as usize;
+        debug_assert!(start >= self.start && start + text.len() <= self.end);
+
+        Self {
+            input: self.input,
+            start,
+            end: start + text.len(),
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        &self.input[self.start..self.end]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The part of the span in the byte range `range`, relative to the start of the span.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        self.sub(&self.as_str()[range])
+    }
+
+    /// The empty span at the end of this one, for errors about missing input.
+    pub fn end(&self) -> Self {
+        self.slice(self.end - self.start..self.end - self.start)
+    }
+
+    /// The line and column of the start of the span, both counted from 1.
+    pub fn location(&self) -> (usize, usize) {
+        let before = &self.input[..self.start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+
+    /// An error pointing at this span.
+    pub fn error(&self, kind: ParseErrorKind) -> ParseError {
+        let (line, column) = self.location();
+        let line_start = self.input[..self.start].rfind('\n').map_or(0, |i| i + 1);
+        let source_line = self.input[line_start..].lines().next().unwrap_or_default();
+        let width = self
+            .as_str()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .count();
+
+        ParseError {
+            line,
+            column,
+            kind,
+            source_line: source_line.to_string(),
+            width: width.max(1),
+        }
+    }
+
+    /// An error with a message of the day's own, like one of its error enums.
+    pub fn error_other(&self, message: impl Display) -> ParseError {
+        self.error(ParseErrorKind::Other(message.to_string()))
+    }
+
+    pub fn trim(&self) -> Self {
+        self.sub(self.as_str().trim())
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        span.as_str().lines().map(move |line| span.sub(line))
+    }
+
+    /// The blocks of lines separated by blank lines, without the blank lines.
+    pub fn records(&self) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        let mut lines = span.lines().peekable();
+
+        std::iter::from_fn(move || {
+            while lines.peek()?.as_str().trim().is_empty() {
+                lines.next();
+            }
+
+            let first = lines.next()?;
+            let mut last = first;
+            while let Some(line) = lines.next_if(|line| !line.as_str().trim().is_empty()) {
+                last = line;
+            }
+
+            Some(Span {
+                input: span.input,
+                start: first.start,
+                end: last.end,
+            })
+        })
+    }
+
+    pub fn split(&self, separator: &'a str) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        span.as_str()
+            .split(separator)
+            .map(move |item| span.sub(item))
+    }
+
+    /// The items of a `separator` separated list, trimmed.
+    pub fn items(&self, separator: &'a str) -> impl Iterator<Item = Span<'a>> {
+        self.split(separator).map(|item| item.trim())
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        span.as_str()
+            .split_whitespace()
+            .map(move |word| span.sub(word))
+    }
+
+    /// The parts before and after the first `separator`.
+    pub fn split_once(&self, separator: &str) -> Result<(Span<'a>, Span<'a>), ParseError> {
+        let text = self.as_str();
+        let i = text.find(separator).ok_or_else(|| {
+            self.error(ParseErrorKind::Expected {
+                want: separator.to_string(),
+                got: text.to_string(),
+            })
+        })?;
+
+        Ok((
+            self.slice(0..i),
+            self.slice(i + separator.len()..text.len()),
+        ))
+    }
+
+    /// A `key<separator>value` pair, both trimmed.
+    pub fn key_value(&self, separator: &str) -> Result<(Span<'a>, Span<'a>), ParseError> {
+        let (key, value) = self.split_once(separator)?;
+        Ok((key.trim(), value.trim()))
+    }
+
+    pub fn strip_prefix(&self, prefix: &str) -> Result<Span<'a>, ParseError> {
+        match self.as_str().strip_prefix(prefix) {
+            Some(rest) => Ok(self.sub(rest)),
+            None => Err(self.error(ParseErrorKind::Expected {
+                want: prefix.to_string(),
+                got: self.as_str().to_string(),
+            })),
+        }
+    }
+
+    pub fn strip_suffix(&self, suffix: &str) -> Result<Span<'a>, ParseError> {
+        match self.as_str().strip_suffix(suffix) {
+            Some(rest) => Ok(self.sub(rest)),
+            None => Err(self.error(ParseErrorKind::Expected {
+                want: suffix.to_string(),
+                got: self.as_str().to_string(),
+            })),
+        }
+    }
+
+    /// Parses the whole span as a `T`.
+    pub fn parse<T>(&self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.as_str().parse().map_err(|e: T::Err| {
+            self.error(ParseErrorKind::InvalidValue {
+                value: self.as_str().to_string(),
+                reason: e.to_string(),
+            })
+        })
+    }
+
+    /// Parses each item of a `separator` separated list, like `1,2,3`.
+    pub fn parse_list<T>(&self, separator: &'a str) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.items(separator).map(|item| item.parse()).collect()
+    }
+}
+
+/// Takes the next item of `iter`, or fails at the end of `span` with what was `expected`.
+pub fn next<'a>(
+    iter: &mut impl Iterator<Item = Span<'a>>,
+    span: &Span<'a>,
+    expected: &str,
+) -> Result<Span<'a>, ParseError> {
+    iter.next().ok_or_else(|| {
+        span.end()
+            .error(ParseErrorKind::UnexpectedEnd(expected.to_string()))
+    })
+}
+
+/// The lines with their numbers, counting from 1, without the blank lines at the start
+/// and end of the input. Unlike trimming the input first, the numbers are the ones in the
+/// file.
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let lines: Vec<&str> = input.lines().collect();
+    let is_blank = |line: &&str| line.trim().is_empty();
+    let start = lines
+        .iter()
+        .position(|line| !is_blank(line))
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|line| !is_blank(line))
+        .map_or(start, |i| i + 1);
+
+    (start..end).map(move |i| (i + 1, lines[i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbered_lines() {
+        let lines: Vec<_> = numbered_lines("\n  \na\n\nb\n\n").collect();
+        assert_eq!(lines, vec![(3, "a"), (4, ""), (5, "b")]);
+
+        assert_eq!(numbered_lines("").count(), 0);
+        assert_eq!(numbered_lines("\n \n").count(), 0);
+    }
+
+    #[test]
+    fn test_location() {
+        let input = Span::new("ab\ncdé f\n");
+
+        let words: Vec<_> = input
+            .words()
+            .map(|word| (word.as_str(), word.location()))
+            .collect();
+        assert_eq!(words, vec![("ab", (1, 1)), ("cdé", (2, 1)), ("f", (2, 5))]);
+        assert_eq!(input.end().location(), (3, 1));
+    }
+
+    #[test]
+    fn test_records() {
+        let input = Span::new("\na: 1\nb: 2\n\n\nc: 3\n");
+
+        let records: Vec<_> = input
+            .records()
+            .map(|record| (record.as_str(), record.location().0))
+            .collect();
+        assert_eq!(records, vec![("a: 1\nb: 2", 2), ("c: 3", 6)]);
+
+        let pairs = input
+            .records()
+            .flat_map(|record| record.lines())
+            .map(|line| {
+                let (key, value) = line.key_value(":")?;
+                Ok((key.as_str(), value.parse::<u32>()?))
+            })
+            .collect::<Result<Vec<_>, ParseError>>();
+        assert_eq!(pairs, Ok(vec![("a", 1), ("b", 2), ("c", 3)]));
+    }
+
+    #[test]
+    fn test_lists() -> Result<(), ParseError> {
+        let input = Span::new("3 = 1, 2,3");
+
+        let (left, right) = input.split_once(" = ")?;
+        assert_eq!(left.parse::<u8>()?, 3);
+        assert_eq!(right.parse_list::<u8>(",")?, vec![1, 2, 3]);
+        assert_eq!(
+            Span::new("mem[8]")
+                .strip_prefix("mem[")?
+                .strip_suffix("]")?
+                .parse::<u64>()?,
+            8
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let input = Span::new("a: 1\nb: 2,x,4\nc");
+        let mut lines = input.lines();
+        lines.next();
+
+        let err = lines
+            .next()
+            .unwrap()
+            .key_value(":")
+            .and_then(|(_, value)| value.parse_list::<u32>(","));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "invalid input on line 2, column 6: invalid value: 'x', invalid digit found in string"
+        );
+
+        let err = lines.next().unwrap().split_once(":").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input on line 3, column 1: expected ':', found 'c'"
+        );
+
+        let err = next(&mut lines, &input, "a fourth line").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input on line 3, column 2: unexpected end of input, expected a fourth line"
+        );
+    }
+
+    #[test]
+    fn test_snippet() {
+        let err = Span::new("1,2\n10,abc,3")
+            .lines()
+            .nth(1)
+            .unwrap()
+            .parse_list::<u32>(",")
+            .unwrap_err();
+
+        assert_eq!(err.snippet(), "  |\n2 | 10,abc,3\n  |    ^^^");
+        assert!(
+            format!("{:?}", Err::<(), _>(err).with_snippet().unwrap_err()).contains("10,abc,3")
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Input profiles, sets of inputs kept side by side in `<data_dir>/<profile>/dayN.txt`,
+//! e.g. one per person, and comparing their answers.
+
+use crate::output::Record;
+use eyre::{bail, Result, WrapErr};
+use std::{fs, path::Path};
+
+/// Whether `dir` holds at least one `dayN.txt` input.
+fn has_inputs(dir: &Path) -> bool {
+    fs::read_dir(dir).is_ok_and(|entries| {
+        entries.filter_map(|entry| entry.ok()).any(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("day")
+                .and_then(|name| name.strip_suffix(".txt"))
+                .is_some_and(|day| day.parse::<u8>().is_ok())
+        })
+    })
+}
+
+/// The profiles in `data_dir`, the subdirectories with inputs in them, sorted by name.
+pub fn discover(data_dir: &Path) -> Result<Vec<String>> {
+    let entries = fs::read_dir(data_dir)
+        .wrap_err_with(|| format!("failed to list the profiles in {}", data_dir.display()))?;
+
+    let mut profiles = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && has_inputs(&entry.path()) {
+            profiles.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    profiles.sort();
+
+    Ok(profiles)
+}
+
+/// The profiles named on the command line, `all` selects every profile in `data_dir`.
+pub fn resolve(data_dir: &Path, names: &[String]) -> Result<Vec<String>> {
+    if names.iter().any(|name| name == "all") {
+        let profiles = discover(data_dir)?;
+        if profiles.is_empty() {
+            bail!(
+                "no profiles in {}, expected directories with dayN.txt inputs",
+                data_dir.display()
+            );
+        }
+        return Ok(profiles);
+    }
+
+    for name in names {
+        if !data_dir.join(name).is_dir() {
+            bail!(
+                "profile '{}' does not exist, expected the directory {}",
+                name,
+                data_dir.join(name).display()
+            );
+        }
+    }
+
+    Ok(names.to_vec())
+}
+
+/// The answers of each profile in a column next to each other, one row per part. The
+/// failed parts only say `error`, the errors are in [`crate::output::summary`].
+pub fn side_by_side(profiles: &[String], records: &[Record]) -> String {
+    let mut parts: Vec<(u8, u8)> = Vec::new();
+    for record in records {
+        if !parts.contains(&(record.day, record.part)) {
+            parts.push((record.day, record.part));
+        }
+    }
+    parts.sort_unstable();
+
+    let cell = |profile: &str, (day, part): (u8, u8)| {
+        let record = records
+            .iter()
+            .find(|r| r.profile.as_deref() == Some(profile) && r.day == day && r.part == part);
+        match record {
+            Some(Record { error: Some(_), .. }) => "error".to_string(),
+            Some(Record {
+                answer: Some(answer),
+                ..
+            }) => answer.clone(),
+            _ => "-".to_string(),
+        }
+    };
+
+    let mut header = vec!["day".to_string(), "part".to_string()];
+    header.extend(profiles.iter().cloned());
+    let mut rows = vec![header];
+    for &(day, part) in &parts {
+        let mut row = vec![day.to_string(), part.to_string()];
+        row.extend(profiles.iter().map(|profile| cell(profile, (day, part))));
+        rows.push(row);
+    }
+
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Part;
+    use eyre::eyre;
+    use std::{env, time::Duration};
+
+    #[test]
+    fn test_resolve() -> Result<()> {
+        let data_dir = env::temp_dir().join(format!("aoc2020_profiles_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&data_dir);
+        for (dir, file) in &[
+            ("bob", "day1.txt"),
+            ("alice", "day12.txt"),
+            ("notes", "todo.txt"),
+        ] {
+            fs::create_dir_all(data_dir.join(dir))?;
+            fs::write(data_dir.join(dir).join(file), "")?;
+        }
+        fs::write(data_dir.join("day1.txt"), "")?;
+
+        assert_eq!(discover(&data_dir)?, vec!["alice", "bob"]);
+        assert_eq!(
+            resolve(&data_dir, &["all".to_string()])?,
+            vec!["alice", "bob"]
+        );
+        assert_eq!(resolve(&data_dir, &["bob".to_string()])?, vec!["bob"]);
+        assert!(resolve(&data_dir, &["carol".to_string()]).is_err());
+        assert!(resolve(&data_dir.join("bob"), &["all".to_string()]).is_err());
+
+        fs::remove_dir_all(&data_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_side_by_side() {
+        let record = |profile: &str, day, part, result: Result<String>| {
+            Record::new(day, part, result, Duration::from_millis(1)).with_profile(profile)
+        };
+        let records = vec![
+            record("alice", 1, Part::One, Ok("751776".into())),
+            record("alice", 1, Part::Two, Ok("42275090".into())),
+            record("bob", 1, Part::One, Ok("1016619".into())),
+            record("bob", 1, Part::Two, Err(eyre!("no triple"))),
+        ];
+        let profiles = vec!["alice".to_string(), "bob".to_string()];
+
+        assert_eq!(
+            side_by_side(&profiles, &records),
+            "day  part  alice     bob\n\
+             1    1     751776    1016619\n\
+             1    2     42275090  error"
+        );
+    }
+}
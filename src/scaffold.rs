@@ -0,0 +1,279 @@
+//! Creating the module and data files for a new day.
+
+use eyre::{bail, eyre, Result, WrapErr};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The module created for a new day, `{day}` is replaced with the day number.
+const TEMPLATE: &str = r#"//! Day {day}
+
+use crate::{parse, solution::Solution};
+use eyre::{Result, WrapErr};
+use thiserror::Error;
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    type Input = Vec<String>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(part_1(input))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(part_2(input))
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LineParseError {
+    #[error("empty line")]
+    EmptyLine,
+}
+
+fn parse_line(line: &str) -> Result<String, LineParseError> {
+    match line.trim() {
+        "" => Err(LineParseError::EmptyLine),
+        line => Ok(line.to_string()),
+    }
+}
+
+/// Parses one line at a time.
+pub fn parse_input(input: &str) -> Result<Vec<String>> {
+    parse::numbered_lines(input)
+        .map(|(number, line)| {
+            parse_line(line).wrap_err_with(|| format!("invalid input on line {}", number))
+        })
+        .collect()
+}
+
+pub fn part_1(input: &[String]) -> usize {
+    input.len()
+}
+
+pub fn part_2(input: &[String]) -> usize {
+    input.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_1() -> Result<()> {
+        let input = parse_input(include_str!("../data/day{day}_test.txt"))?;
+        assert_eq!(part_1(&input), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_2() -> Result<()> {
+        let input = parse_input(include_str!("../data/day{day}_test.txt"))?;
+        assert_eq!(part_2(&input), 0);
+
+        Ok(())
+    }
+}
+"#;
+
+pub fn template(day: u8) -> String {
+    TEMPLATE.replace("{day}", &day.to_string())
+}
+
+fn module_name(line: &str) -> &str {
+    line.trim_start_matches("pub mod ").trim_end_matches(';')
+}
+
+/// Adds `pub mod dayN;` and the registry entry for `day` to the source of `lib.rs`.
+///
+/// The module goes in sorted order among the other `mod` lines and the entry at the
+/// end of `SOLUTIONS`.
+pub fn register(lib_rs: &str, day: u8) -> Result<String> {
+    let module = format!("pub mod day{};", day);
+    let entry = format!("    ({}, &day{}::Day{}),", day, day, day);
+
+    let mut lines: Vec<String> = lib_rs.lines().map(String::from).collect();
+
+    if lines.contains(&module) {
+        bail!("day {} is already registered", day);
+    }
+
+    let mod_lines: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.starts_with("pub mod "))
+        .map(|(i, _)| i)
+        .collect();
+    let last_mod = *mod_lines
+        .last()
+        .ok_or_else(|| eyre!("no mod declarations found"))?;
+    let mod_index = mod_lines
+        .iter()
+        .cloned()
+        .find(|&i| module_name(&lines[i]) > module_name(&module))
+        .unwrap_or(last_mod + 1);
+    lines.insert(mod_index, module);
+
+    let solutions = lines
+        .iter()
+        .position(|line| line.starts_with("static SOLUTIONS"))
+        .ok_or_else(|| eyre!("no SOLUTIONS registry found"))?;
+    let end = lines[solutions..]
+        .iter()
+        .position(|line| line == "];")
+        .ok_or_else(|| eyre!("the SOLUTIONS registry is not terminated"))?;
+    lines.insert(solutions + end, entry);
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+
+    Ok(output)
+}
+
+/// Creates the module and data files for `day` under `root` and registers it in
+/// `src/lib.rs`, returns the created files.
+///
+/// Nothing is written if any of the files already exist.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let lib_path = root.join("src").join("lib.rs");
+    let files = vec![
+        (
+            root.join("src").join(format!("day{}.rs", day)),
+            template(day),
+        ),
+        (
+            root.join("data").join(format!("day{}.txt", day)),
+            String::new(),
+        ),
+        (
+            root.join("data").join(format!("day{}_test.txt", day)),
+            String::new(),
+        ),
+    ];
+
+    let existing: Vec<_> = files
+        .iter()
+        .filter(|(path, _)| path.exists())
+        .map(|(path, _)| path.display().to_string())
+        .collect();
+    if !existing.is_empty() {
+        bail!("refusing to overwrite {}", existing.join(", "));
+    }
+
+    let lib_rs = fs::read_to_string(&lib_path)
+        .wrap_err_with(|| format!("failed to read {}", lib_path.display()))?;
+    let lib_rs = register(&lib_rs, day)?;
+
+    for (path, contents) in &files {
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .wrap_err_with(|| format!("failed to create {}", path.display()))?;
+    }
+
+    fs::write(&lib_path, lib_rs)
+        .wrap_err_with(|| format!("failed to write {}", lib_path.display()))?;
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB_RS: &str = "pub mod answers;
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day20;
+pub mod input;
+
+static SOLUTIONS: &[(u8, &dyn DynSolution)] = &[
+    (1, &day1::Day1),
+    (2, &day2::Day2),
+    (10, &day10::Day10),
+];
+";
+
+    #[test]
+    fn test_template() {
+        let module = template(21);
+
+        assert!(module.contains("pub struct Day21;"));
+        assert!(module.contains("impl Solution for Day21 {"));
+        assert!(module.contains("include_str!(\"../data/day21_test.txt\")"));
+        assert!(!module.contains("{day}"));
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let tests = vec![
+            (
+                3,
+                "pub mod day20;\npub mod day3;\npub mod input;",
+                "(3, &day3::Day3),\n];",
+            ),
+            (
+                11,
+                "pub mod day10;\npub mod day11;\npub mod day2;",
+                "(11, &day11::Day11),\n];",
+            ),
+            (
+                21,
+                "pub mod day20;\npub mod day21;\npub mod input;",
+                "(21, &day21::Day21),\n];",
+            ),
+        ];
+
+        for (day, want_mod, want_entry) in tests {
+            let got = register(LIB_RS, day)?;
+            assert!(got.contains(want_mod), "day {}:\n{}", day, got);
+            assert!(got.contains(want_entry), "day {}:\n{}", day, got);
+        }
+
+        assert!(register(LIB_RS, 10).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_day() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("aoc2020-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src"))?;
+        fs::create_dir_all(root.join("data"))?;
+        fs::write(root.join("src").join("lib.rs"), LIB_RS)?;
+
+        let created = new_day(&root, 3)?;
+        assert_eq!(created.len(), 3);
+        assert!(created.iter().all(|path| path.exists()));
+        assert!(fs::read_to_string(root.join("src").join("lib.rs"))?.contains("pub mod day3;"));
+
+        // the second time everything already exists
+        assert!(new_day(&root, 3).is_err());
+
+        // an existing data file stops the day from being created at all
+        fs::write(root.join("data").join("day4.txt"), "1\n2\n")?;
+        assert!(new_day(&root, 4).is_err());
+        assert!(!root.join("src").join("day4.rs").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("data").join("day4.txt"))?,
+            "1\n2\n"
+        );
+
+        fs::remove_dir_all(&root)?;
+
+        Ok(())
+    }
+}
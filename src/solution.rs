@@ -0,0 +1,134 @@
+//! The [`Solution`] trait every day implements.
+
+use eyre::{eyre, Result};
+use std::{any::Any, fmt::Display, str::FromStr};
+use thiserror::Error;
+
+/// A solution for one day of the advent calendar.
+///
+/// The input is parsed once and then shared by both parts.
+pub trait Solution {
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1>;
+    fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2>;
+}
+
+/// Input parsed by [`DynSolution::parse_input`].
+pub type Parsed = Box<dyn Any>;
+
+/// Object safe version of [`Solution`], this is what the registry holds.
+pub trait DynSolution: Sync {
+    fn parse_input(&self, input: &str) -> Result<Parsed>;
+    fn solve(&self, parsed: &Parsed, part: Part) -> Result<String>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn parse_input(&self, input: &str) -> Result<Parsed> {
+        Ok(Box::new(self.parse(input)?))
+    }
+
+    fn solve(&self, parsed: &Parsed, part: Part) -> Result<String> {
+        let input = parsed
+            .downcast_ref::<S::Input>()
+            .ok_or_else(|| eyre!("parsed input does not belong to this solution"))?;
+
+        match part {
+            Part::One => Ok(self.part_1(input)?.to_string()),
+            Part::Two => Ok(self.part_2(input)?.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn all() -> [Part; 2] {
+        [Part::One, Part::Two]
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PartParseError {
+    #[error("invalid part: '{0}', expected 1 or 2")]
+    InvalidPart(String),
+}
+
+impl FromStr for Part {
+    type Err = PartParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(PartParseError::InvalidPart(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+        type Answer1 = u32;
+        type Answer2 = String;
+
+        fn parse(&self, input: &str) -> Result<Self::Input> {
+            Ok(input
+                .split(',')
+                .map(u32::from_str)
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part_1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+            Ok(input.iter().sum())
+        }
+
+        fn part_2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+            Ok(format!("{} numbers", input.len()))
+        }
+    }
+
+    #[test]
+    fn test_dyn_solution() -> Result<()> {
+        let solution: &dyn DynSolution = &Sum;
+        let parsed = solution.parse_input("1,2,3")?;
+
+        assert_eq!(solution.solve(&parsed, Part::One)?, "6");
+        assert_eq!(solution.solve(&parsed, Part::Two)?, "3 numbers");
+        assert!(solution.parse_input("1,x").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_part() {
+        assert_eq!(Part::from_str("1"), Ok(Part::One));
+        assert_eq!(Part::from_str("2"), Ok(Part::Two));
+        assert!(Part::from_str("3").is_err());
+    }
+}
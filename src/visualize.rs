@@ -0,0 +1,506 @@
+//! Animating how the grid based days evolve, one frame per step, in the terminal.
+
+use crate::{day11, day17, day20, solution::Part};
+use eyre::{bail, Result, WrapErr};
+use std::{
+    env,
+    io::{self, Read, Write},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+/// One step of a day, e.g. a round of the seating system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub title: String,
+    /// The state after the step, one row per line
+    pub text: String,
+}
+
+impl Frame {
+    pub fn new(title: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            text: text.into(),
+        }
+    }
+}
+
+/// The frames of `day` for `input`. The part picks the seating rules of day 11 and the
+/// number of dimensions of day 17, `width` is how many columns the frames may use.
+pub fn frames(day: u8, input: &str, part: Part, width: usize) -> Result<Vec<Frame>> {
+    match day {
+        11 => day11::frames(input, part),
+        17 => day17::frames(input, part, width),
+        20 => day20::frames(input),
+        _ => bail!("no visualization for day {}, expected 11, 17 or 20", day),
+    }
+}
+
+/// Lays out labelled blocks of text side by side, starting a new row of blocks when the
+/// next one would not fit in `width` columns.
+pub fn side_by_side(blocks: &[(String, String)], width: usize) -> String {
+    let mut rows: Vec<Vec<&(String, String)>> = Vec::new();
+    let mut used = 0;
+
+    for block in blocks {
+        let block_width = block_width(block);
+        match rows.last_mut() {
+            Some(row) if used + 2 + block_width <= width => {
+                row.push(block);
+                used += 2 + block_width;
+            }
+            _ => {
+                rows.push(vec![block]);
+                used = block_width;
+            }
+        }
+    }
+
+    rows.iter()
+        .map(|row| {
+            let height = row.iter().map(|(_, text)| text.lines().count()).max();
+            let lines = (0..=height.unwrap_or(0)).map(|i| {
+                let cells: Vec<String> = row
+                    .iter()
+                    .map(|block| {
+                        let (label, text) = block;
+                        let cell = if i == 0 {
+                            label.as_str()
+                        } else {
+                            text.lines().nth(i - 1).unwrap_or_default()
+                        };
+                        format!("{:<width$}", cell, width = block_width(block))
+                    })
+                    .collect();
+                cells.join("  ").trim_end().to_string()
+            });
+            lines.collect::<Vec<_>>().join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn block_width((label, text): &(String, String)) -> usize {
+    text.lines()
+        .map(|line| line.chars().count())
+        .chain(Some(label.chars().count()))
+        .max()
+        .unwrap_or(0)
+}
+
+/// What a key press does to the animation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    PlayPause,
+    Next,
+    Previous,
+    First,
+    Last,
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl Key {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            ' ' | 'p' => Some(Key::PlayPause),
+            'n' | 'l' | '.' => Some(Key::Next),
+            'b' | 'h' | ',' => Some(Key::Previous),
+            'g' | '0' => Some(Key::First),
+            'G' | '$' => Some(Key::Last),
+            '+' | '=' => Some(Key::Faster),
+            '-' => Some(Key::Slower),
+            // ctrl-c, as the terminal does not turn it into a signal in raw mode
+            'q' | '\x1b' | '\x03' => Some(Key::Quit),
+            _ => None,
+        }
+    }
+}
+
+const KEYS_HELP: &str = "space play/pause  n/b step  g/G first/last  +/- speed  q quit";
+
+/// The frame that is shown, whether the frames are playing and how fast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Player {
+    frames: Vec<Frame>,
+    current: usize,
+    playing: bool,
+    delay: Duration,
+}
+
+impl Player {
+    /// Starts playing the frames from the first one, showing each for `delay`.
+    pub fn new(frames: Vec<Frame>, delay: Duration) -> Self {
+        Self {
+            frames,
+            current: 0,
+            playing: true,
+            delay,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    fn last(&self) -> usize {
+        self.frames.len().saturating_sub(1)
+    }
+
+    /// Applies a key press, false if it was [`Key::Quit`]. Stepping pauses the animation.
+    pub fn press(&mut self, key: Key) -> bool {
+        match key {
+            Key::PlayPause if self.current == self.last() => {
+                self.current = 0;
+                self.playing = true;
+            }
+            Key::PlayPause => self.playing = !self.playing,
+            Key::Next => {
+                self.playing = false;
+                self.current = (self.current + 1).min(self.last());
+            }
+            Key::Previous => {
+                self.playing = false;
+                self.current = self.current.saturating_sub(1);
+            }
+            Key::First => {
+                self.playing = false;
+                self.current = 0;
+            }
+            Key::Last => {
+                self.playing = false;
+                self.current = self.last();
+            }
+            Key::Faster => self.delay = (self.delay / 2).max(Duration::from_millis(10)),
+            Key::Slower => self.delay = (self.delay * 2).min(Duration::from_secs(10)),
+            Key::Quit => return false,
+        }
+
+        true
+    }
+
+    /// Moves on to the next frame while playing, and stops at the last one.
+    pub fn tick(&mut self) {
+        if self.playing {
+            self.current = (self.current + 1).min(self.last());
+            self.playing = self.current < self.last();
+        }
+    }
+
+    /// The screen: a status line, the frame title and as much of the frame as fits in
+    /// `height` lines, with the cells coloured by [`colorize`] if `color` is set.
+    pub fn render(&self, height: usize, color: bool) -> String {
+        let frame = match self.frames.get(self.current) {
+            Some(frame) => frame,
+            None => return "no frames".to_string(),
+        };
+
+        let status = format!(
+            "frame {}/{}  {}  {}ms  {}",
+            self.current + 1,
+            self.frames.len(),
+            if self.playing { "playing" } else { "paused" },
+            self.delay.as_millis(),
+            KEYS_HELP,
+        );
+
+        let rows = height.saturating_sub(3).max(1);
+        let text: Vec<String> = frame
+            .text
+            .lines()
+            .map(|line| {
+                if color {
+                    colorize(line)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        let mut lines = vec![status, frame.title.clone()];
+        if text.len() > rows {
+            lines.extend(text[..rows - 1].iter().cloned());
+            lines.push(format!("({} more lines)", text.len() - (rows - 1)));
+        } else {
+            lines.extend(text);
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Colours the cells of a frame: `#` bright, `O` (the sea monsters) green and the
+/// empty seats and space dim.
+pub fn colorize(text: &str) -> String {
+    let mut colored = String::with_capacity(text.len() * 2);
+    for c in text.chars() {
+        match c {
+            '#' => colored += "\x1b[1;33m#\x1b[0m",
+            'O' => colored += "\x1b[1;32mO\x1b[0m",
+            'L' | '.' => {
+                colored += "\x1b[2m";
+                colored.push(c);
+                colored += "\x1b[0m";
+            }
+            _ => colored.push(c),
+        }
+    }
+    colored
+}
+
+/// The number of columns and rows of the terminal, from the terminal itself or the
+/// `COLUMNS` and `LINES` environment variables, 80 by 24 if neither is known.
+pub fn terminal_size() -> (usize, usize) {
+    #[cfg(unix)]
+    {
+        // SAFETY: TIOCGWINSZ only writes the window size into `size`
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0
+            && size.ws_col > 0
+            && size.ws_row > 0
+        {
+            return (usize::from(size.ws_col), usize::from(size.ws_row));
+        }
+    }
+
+    let from_env = |name: &str, default: usize| {
+        env::var(name)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+    };
+    (from_env("COLUMNS", 80), from_env("LINES", 24))
+}
+
+/// Puts the terminal in raw mode, so key presses are read without waiting for enter and
+/// are not echoed, and restores it when dropped.
+struct RawMode {
+    #[cfg(unix)]
+    original: Option<libc::termios>,
+}
+
+impl RawMode {
+    fn enable() -> Self {
+        #[cfg(unix)]
+        {
+            // SAFETY: the termios structs are only written by tcgetattr
+            let mut original: libc::termios = unsafe { std::mem::zeroed() };
+            if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+                // not a terminal, the keys are read a line at a time
+                return Self { original: None };
+            }
+
+            let mut raw = original;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+            raw.c_cc[libc::VMIN] = 1;
+            raw.c_cc[libc::VTIME] = 0;
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) };
+
+            Self {
+                original: Some(original),
+            }
+        }
+
+        #[cfg(not(unix))]
+        Self {}
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(original) = &self.original {
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original) };
+        }
+    }
+}
+
+/// Reads the key presses from stdin on another thread, the channel disconnects when
+/// stdin is closed.
+fn read_keys() -> Receiver<Key> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        for byte in stdin.lock().bytes() {
+            let key = match byte {
+                Ok(byte) => Key::from_char(char::from(byte)),
+                Err(_) => break,
+            };
+            if let Some(key) = key {
+                if sender.send(key).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    receiver
+}
+
+/// Plays the frames in the terminal until `q` is pressed. When stdin is not a terminal
+/// the frames are played once.
+pub fn play(frames: Vec<Frame>, delay: Duration) -> Result<()> {
+    let mut player = Player::new(frames, delay);
+    let keys = read_keys();
+    let mut keys_open = true;
+
+    let _raw_mode = RawMode::enable();
+    let mut stdout = io::stdout();
+    // the alternate screen, without the cursor
+    write!(stdout, "\x1b[?1049h\x1b[?25l")?;
+
+    let result = (|| -> Result<()> {
+        loop {
+            let (_, height) = terminal_size();
+            let screen = player.render(height, true).replace('\n', "\r\n");
+            write!(stdout, "\x1b[H\x1b[2J{}", screen)?;
+            stdout.flush().wrap_err("failed to draw the frame")?;
+
+            let key = if player.is_playing() || !keys_open {
+                match keys.recv_timeout(player.delay()) {
+                    Ok(key) => Some(key),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        keys_open = false;
+                        if !player.is_playing() {
+                            return Ok(());
+                        }
+                        thread::sleep(player.delay());
+                        None
+                    }
+                }
+            } else {
+                keys.recv().ok()
+            };
+
+            match key {
+                Some(key) if !player.press(key) => return Ok(()),
+                Some(_) => {}
+                None => player.tick(),
+            }
+        }
+    })();
+
+    write!(stdout, "\x1b[?25h\x1b[?1049l")?;
+    stdout.flush()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player() -> Player {
+        let frames = (0..3)
+            .map(|i| Frame::new(format!("step {}", i), format!("{}\n.#.", i)))
+            .collect();
+        Player::new(frames, Duration::from_millis(100))
+    }
+
+    #[test]
+    fn test_frames() -> Result<()> {
+        let seats = frames(
+            11,
+            include_str!("../data/day11_test_1_step_1.txt"),
+            Part::One,
+            80,
+        )?;
+        assert_eq!(seats.len(), 6, "the start and five rounds");
+        assert_eq!(seats[5].title, "round 5, 37 occupied");
+
+        let cubes = frames(17, include_str!("../data/day17_test.txt"), Part::One, 80)?;
+        assert_eq!(cubes.len(), 7);
+        assert_eq!(cubes[6].title, "cycle 6, 112 active");
+
+        let sea = frames(20, include_str!("../data/day20_test.txt"), Part::One, 80)?;
+        let last = sea.last().unwrap();
+        assert!(last.title.ends_with("273 rough water"), "{}", last.title);
+        assert_eq!(last.text.matches('O').count(), 2 * 15);
+
+        assert!(frames(1, "", Part::One, 80).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_side_by_side() {
+        let block = |label: &str, text: &str| (label.to_string(), text.to_string());
+        let blocks = vec![
+            block("z=-1", "#..\n..#"),
+            block("z=0", "#.#\n.#."),
+            block("z=1", "#..\n..#"),
+        ];
+
+        assert_eq!(
+            side_by_side(&blocks, 10),
+            "z=-1  z=0\n#..   #.#\n..#   .#.\n\nz=1\n#..\n..#"
+        );
+        assert_eq!(
+            side_by_side(&blocks, 80),
+            "z=-1  z=0  z=1\n#..   #.#  #..\n..#   .#.  ..#"
+        );
+    }
+
+    #[test]
+    fn test_player() {
+        let mut player = player();
+        assert!(player.is_playing());
+
+        player.tick();
+        player.tick();
+        assert_eq!(player.current(), 2);
+        assert!(!player.is_playing(), "stops at the last frame");
+
+        player.press(Key::PlayPause);
+        assert_eq!(
+            (player.current(), player.is_playing()),
+            (0, true),
+            "plays again from the start"
+        );
+
+        player.press(Key::Next);
+        assert_eq!((player.current(), player.is_playing()), (1, false));
+        player.tick();
+        assert_eq!(player.current(), 1, "paused");
+        player.press(Key::Previous);
+        player.press(Key::Previous);
+        assert_eq!(player.current(), 0);
+        player.press(Key::Last);
+        assert_eq!(player.current(), 2);
+
+        player.press(Key::Faster);
+        assert_eq!(player.delay(), Duration::from_millis(50));
+        player.press(Key::Slower);
+        player.press(Key::Slower);
+        assert_eq!(player.delay(), Duration::from_millis(200));
+
+        assert!(!player.press(Key::Quit));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut player = player();
+        player.press(Key::Next);
+
+        let screen = player.render(24, false);
+        assert!(screen.starts_with("frame 2/3  paused  100ms"));
+        assert!(screen.ends_with("step 1\n1\n.#."));
+
+        let screen = player.render(4, false);
+        assert!(screen.ends_with("step 1\n(2 more lines)"));
+
+        let screen = player.render(24, true);
+        assert!(screen.ends_with("step 1\n1\n\x1b[2m.\x1b[0m\x1b[1;33m#\x1b[0m\x1b[2m.\x1b[0m"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Polling input files for changes and comparing the answers of consecutive runs.
+
+use crate::output::Record;
+use eyre::{Result, WrapErr};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    /// Not polled yet
+    Unknown,
+    /// Could not be read at the last poll
+    Missing,
+    Present {
+        modified: Option<SystemTime>,
+        len: u64,
+        contents: String,
+    },
+}
+
+/// A file that is polled for changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedFile {
+    path: PathBuf,
+    state: State,
+}
+
+impl WatchedFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            state: State::Unknown,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The contents of the file if they changed since the last poll, or the error if it
+    /// can no longer be read. The first poll always reports the file.
+    ///
+    /// The modification time and length are checked first, the file is only read when one
+    /// of them changed, and a file that is saved without changes is not reported.
+    pub fn poll(&mut self) -> Option<Result<String>> {
+        let (modified, len) = match fs::metadata(&self.path) {
+            Ok(metadata) => (metadata.modified().ok(), metadata.len()),
+            Err(e) => return self.missing(e),
+        };
+
+        if let State::Present {
+            modified: last_modified,
+            len: last_len,
+            ..
+        } = &self.state
+        {
+            if *last_modified == modified && *last_len == len {
+                return None;
+            }
+        }
+
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) => return self.missing(e),
+        };
+
+        let changed =
+            !matches!(&self.state, State::Present { contents: last, .. } if *last == contents);
+        self.state = State::Present {
+            modified,
+            len,
+            contents: contents.clone(),
+        };
+
+        if changed {
+            Some(Ok(contents))
+        } else {
+            None
+        }
+    }
+
+    fn missing(&mut self, e: std::io::Error) -> Option<Result<String>> {
+        if self.state == State::Missing {
+            return None;
+        }
+
+        self.state = State::Missing;
+        Some(Err(e).wrap_err_with(|| format!("failed to read {}", self.path.display())))
+    }
+}
+
+/// The record in the plain format, followed by what the answer was in the `previous` run
+/// of the same part, if there was one.
+pub fn format_change(previous: Option<&Record>, record: &Record) -> String {
+    let describe = |record: &Record| match (&record.answer, &record.error) {
+        (_, Some(error)) => format!("error: {}", error),
+        (Some(answer), None) => answer.clone(),
+        (None, None) => String::new(),
+    };
+
+    let current = describe(record);
+    let line = format!("day {} part {}: {}", record.day, record.part, current);
+
+    match previous.map(describe) {
+        None => line,
+        Some(previous) if previous == current => format!("{} (unchanged)", line),
+        Some(previous) if previous.starts_with("error: ") => format!("{} (was an error)", line),
+        Some(previous) => format!("{} (was {})", line, previous),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Part;
+    use eyre::eyre;
+    use std::{env, time::Duration};
+
+    #[test]
+    fn test_poll() -> Result<()> {
+        let path = env::temp_dir().join(format!("aoc2020_watch_{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut file = WatchedFile::new(&path);
+
+        assert!(matches!(file.poll(), Some(Err(_))));
+        assert!(
+            file.poll().is_none(),
+            "a missing file is only reported once"
+        );
+
+        fs::write(&path, "1\n2\n")?;
+        assert_eq!(file.poll().transpose()?, Some("1\n2\n".to_string()));
+        assert_eq!(file.poll().transpose()?, None);
+
+        fs::write(&path, "1\n2\n3\n")?;
+        assert_eq!(file.poll().transpose()?, Some("1\n2\n3\n".to_string()));
+
+        fs::write(&path, "1\n2\n3\n")?;
+        assert_eq!(
+            file.poll().transpose()?,
+            None,
+            "saving without changes is not a change"
+        );
+
+        fs::remove_file(&path)?;
+        assert!(matches!(file.poll(), Some(Err(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_change() {
+        let record =
+            |result: Result<String>| Record::new(10, Part::One, result, Duration::from_millis(1));
+        let answer = record(Ok("2470".into()));
+        let other_answer = record(Ok("35".into()));
+        let error = record(Err(eyre!("invalid input")));
+
+        let tests = vec![
+            (None, &answer, "day 10 part 1: 2470"),
+            (Some(&answer), &answer, "day 10 part 1: 2470 (unchanged)"),
+            (Some(&answer), &other_answer, "day 10 part 1: 35 (was 2470)"),
+            (
+                Some(&answer),
+                &error,
+                "day 10 part 1: error: invalid input (was 2470)",
+            ),
+            (Some(&error), &answer, "day 10 part 1: 2470 (was an error)"),
+        ];
+
+        for (previous, current, want) in tests {
+            assert_eq!(format_change(previous, current), want);
+        }
+    }
+}